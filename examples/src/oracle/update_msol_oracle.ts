@@ -64,11 +64,13 @@ async function transactionSenderAndConfirmationWaiter(
     };
 }
 
-function composeUpdatePriceTransaction(connection: Connection, programId: PublicKey, pythAccount: PublicKey, price: number, confidence: number, status: number) {
+function composeUpdatePriceTransaction(connection: Connection, programId: PublicKey, pythAccount: PublicKey, configAccount: PublicKey, price: number, confidence: number, status: number) {
     const transaction = new Transaction();
     const dataLayout = struct([u8('instruction'), ns64('price'), nu64('confidence'), u32('status')]);
+    // the trailing config PDA opts the update into the program-wide pause
     const keys = [
-        { pubkey: pythAccount, isSigner: true, isWritable: true }
+        { pubkey: pythAccount, isSigner: true, isWritable: true },
+        { pubkey: configAccount, isSigner: false, isWritable: false }
     ];
 
     const data = Buffer.alloc(dataLayout.span)
@@ -112,6 +114,7 @@ class Main extends BasicRunnable<Config> {
     private payer!: Keypair;
     private pythAccount!: Keypair;
     private programId!: PublicKey;
+    private configAccount!: PublicKey;
 
     private fetchInterval!: number;
     private updateThreshold!: number;
@@ -137,7 +140,7 @@ class Main extends BasicRunnable<Config> {
 
         const oraclePrice = price ? 1.0 / price : 0;
 
-        const tx = composeUpdatePriceTransaction(this.connection, this.programId, this.pythAccount.publicKey, oraclePrice, this.confidence, status);
+        const tx = composeUpdatePriceTransaction(this.connection, this.programId, this.pythAccount.publicKey, this.configAccount, oraclePrice, this.confidence, status);
         const { blockhash } = await this.connection.getLatestBlockhash();
         tx.recentBlockhash = blockhash;
         tx.sign(this.payer, this.pythAccount);
@@ -169,6 +172,7 @@ class Main extends BasicRunnable<Config> {
         this.payer = Keypair.fromSecretKey(Uint8Array.from(config.payer));
         this.pythAccount = Keypair.fromSecretKey(Uint8Array.from(config.pythAccount));
         this.programId = new PublicKey(config.programId);
+        [this.configAccount] = await PublicKey.findProgramAddress([Buffer.from("config")], this.programId);
 
         this.fetchInterval = config.fetchInterval;
         this.updateThreshold = config.updateThreshold;
@@ -70,11 +70,13 @@ function getMarkPrice(bid: number, ask: number) {
     return (bid + ask) / 2.0;
 }
 
-function composeUpdatePriceTransaction(connection: Connection, programId: PublicKey, pythAccount: PublicKey, price: number, confidence: number, status: number) {
+function composeUpdatePriceTransaction(connection: Connection, programId: PublicKey, pythAccount: PublicKey, configAccount: PublicKey, price: number, confidence: number, status: number) {
     const transaction = new Transaction();
     const dataLayout = struct([u8('instruction'), ns64('price'), nu64('confidence'), u32('status')]);
+    // the trailing config PDA opts the update into the program-wide pause
     const keys = [
-        { pubkey: pythAccount, isSigner: true, isWritable: true }
+        { pubkey: pythAccount, isSigner: true, isWritable: true },
+        { pubkey: configAccount, isSigner: false, isWritable: false }
     ];
 
     const data = Buffer.alloc(dataLayout.span)
@@ -136,6 +138,7 @@ class Main extends BasicRunnable<Config> {
     private payer!: Keypair;
     private pythAccount!: Keypair;
     private programId!: PublicKey;
+    private configAccount!: PublicKey;
     private baseTokenMint!: PublicKey;
     private quoteTokenMint!: PublicKey;
     private baseQuantity!: number;
@@ -200,7 +203,7 @@ class Main extends BasicRunnable<Config> {
     async updatePrice(price: number, status: number, now: number): Promise<void> {
         this.logger.debug(`updating: price=${price.toFixed(8)}, status=${status}, now=${now}`);
 
-        const tx = composeUpdatePriceTransaction(this.connection, this.programId, this.pythAccount.publicKey, price, this.confidence, status);
+        const tx = composeUpdatePriceTransaction(this.connection, this.programId, this.pythAccount.publicKey, this.configAccount, price, this.confidence, status);
         const { blockhash } = await this.connection.getLatestBlockhash();
         tx.recentBlockhash = blockhash;
         tx.sign(this.payer, this.pythAccount);
@@ -232,6 +235,7 @@ class Main extends BasicRunnable<Config> {
         this.payer = Keypair.fromSecretKey(Uint8Array.from(config.payer));
         this.pythAccount = Keypair.fromSecretKey(Uint8Array.from(config.pythAccount));
         this.programId = new PublicKey(config.programId);
+        [this.configAccount] = await PublicKey.findProgramAddress([Buffer.from("config")], this.programId);
 
         this.baseTokenMint = new PublicKey(config.baseTokenMint);
         this.quoteTokenMint = new PublicKey(config.quoteTokenMint);
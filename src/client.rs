@@ -0,0 +1,25 @@
+//! Off-chain helpers for reading oracle accounts and budgeting transactions
+
+use crate::state::{OracleV1, OracleVersion};
+use solana_program::program_error::ProgramError;
+
+/// Decode many packed oracle accounts at once, returning one result per
+/// account in the same order. Each account is checked as by
+/// [OracleVersion::unpack_v1], so uninitialized or zeroed accounts decode to an
+/// error.
+pub fn decode_many(datas: &[&[u8]]) -> Vec<Result<OracleV1, ProgramError>> {
+    datas.iter().map(|data| OracleVersion::unpack_v1(data)).collect()
+}
+
+/// Compute units budgeted for an `Update`, including the global pause check.
+/// A conservative estimate, not a measurement: an update copies the 3312-byte
+/// account in and out once, steps the EMAs and writes one history entry, all
+/// fixed-size work with no loops over the account, and the budget leaves a
+/// wide margin over that. `tests/compute_units.rs` checks it against the BPF
+/// build under `cargo test-bpf`; raise it if that test fails.
+pub const UPDATE_COMPUTE_UNITS: u32 = 25_000;
+
+/// Compute units to request for an `Update` instruction.
+pub fn estimated_compute_units() -> u32 {
+    UPDATE_COMPUTE_UNITS
+}
@@ -0,0 +1,40 @@
+//! Layout and default values shared by the processor and state
+
+/// magic number.
+pub const MAGIC: u32 = 0xa1b2c3d4;
+/// program version.
+pub const VERSION: u32 = 2;
+/// program version of oracles with a full-width price.
+pub const VERSION_V2: u32 = 3;
+/// account type.
+pub const ATYPE: u32 = 3;
+/// account size.
+pub const SIZE: u32 = 3312;
+/// account size of oracles with a full-width price.
+pub const SIZE_V2: u32 = 3328;
+/// price type.
+pub const TYPE: u32 = 1;
+/// price exponent.
+pub const EXPONENT: i32 = -8;
+/// numerator state.
+pub const NUMERATOR: u64 = 0;
+/// denominator state.
+pub const DENOMINATOR: u64 = 0;
+/// number of quoters that make up aggregate.
+pub const NUM_COMPONENT: u32 = 10;
+/// slot of last valid aggregate price.
+pub const NUM_QUOTERS: u32 = 1;
+/// min publishers for valid price.
+pub const MIN_PUBLISHERS: u8 = 1;
+/// notification of any corporate action.
+pub const ACTION: u32 = 0;
+/// number of updates over which older ema samples decay.
+pub const EMA_PERIOD: u64 = 30;
+/// weight given to each new ema sample per elapsed slot.
+pub const EMA_WEIGHT: u64 = 1_000_000;
+/// elapsed slots beyond which an ema sample gains no further weight.
+pub const EMA_MAX_SLOT_DELTA: u64 = 1 << 32;
+/// ema denominator above which numerator and denominator are renormalized.
+pub const EMA_RENORMALIZE_THRESHOLD: u64 = 1 << 48;
+/// common factor ema numerator and denominator are divided by when renormalized.
+pub const EMA_RENORMALIZE_FACTOR: u64 = 1 << 16;
@@ -1,6 +1,7 @@
 //! Error types
 
 use num_derive::FromPrimitive;
+use num_traits::FromPrimitive;
 use solana_program::{decode_error::DecodeError, program_error::ProgramError};
 use thiserror::Error;
 
@@ -16,6 +17,55 @@ pub enum OracleError {
     /// Address of the provided oracle account is incorrect
     #[error("Address of the provided signer account is incorrect")]
     IncorrectSigner,
+    /// Status code does not map to a known price status
+    #[error("Invalid price status")]
+    InvalidStatus,
+    /// The oracle account was not passed as writable
+    #[error("Oracle account is not writable")]
+    AccountNotWritable,
+    /// Confidence is below the oracle's configured floor
+    #[error("Confidence is below the configured minimum")]
+    ConfidenceBelowMinimum,
+    /// Address of the provided authority account is incorrect
+    #[error("Address of the provided authority account is incorrect")]
+    IncorrectAuthority,
+    /// Arithmetic overflowed or divided by zero
+    #[error("Arithmetic overflow or division by zero")]
+    ArithmeticError,
+    /// Price is a sentinel value that can't be published
+    #[error("Invalid price")]
+    InvalidPrice,
+    /// Oracle account is frozen for migration
+    #[error("Oracle account is frozen")]
+    AccountFrozen,
+    /// Signed update was not verified by a matching ed25519 instruction
+    #[error("Invalid update signature")]
+    InvalidSignature,
+    /// Updates are paused program-wide
+    #[error("Program is paused")]
+    ProgramPaused,
+    /// Update timestamp is older than the stored one
+    #[error("Timestamp out of range")]
+    TimestampOutOfRange,
+    /// Component index is beyond the oracle's component slots
+    #[error("Component index out of range")]
+    ComponentIndexOutOfRange,
+    /// Account doesn't hold enough lamports to be rent exempt
+    #[error("Account is not rent exempt")]
+    NotRentExempt,
+    /// Price type is not a known PriceType
+    #[error("Invalid price type")]
+    InvalidPriceType,
+    /// Confidence is as wide as the price, which was kept rather than replaced
+    #[error("Untrusted price")]
+    UntrustedPrice,
+}
+
+impl OracleError {
+    /// Map the code of a `ProgramError::Custom` back to the error it came from
+    pub fn from_code(code: u32) -> Option<Self> {
+        Self::from_u32(code)
+    }
 }
 
 impl From<OracleError> for ProgramError {
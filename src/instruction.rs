@@ -2,16 +2,20 @@
 
 #![allow(clippy::too_many_arguments)]
 
-use crate::error::OracleError;
+use crate::{
+    error::OracleError,
+    state::{OracleFlags, PriceStatus},
+};
 use solana_program::{
     program_error::ProgramError,
+    pubkey::Pubkey,
 };
-use std::convert::TryInto;
+use std::convert::{TryFrom, TryInto};
 use std::mem::size_of;
 
 /// Update instruction data
 #[repr(C)]
-#[derive(Debug, PartialEq)]
+#[derive(Clone, Copy, Debug, PartialEq)]
 pub struct Update {
     /// price used to update oracle data
     pub price: i64,
@@ -21,12 +25,374 @@ pub struct Update {
     pub status: u32,
 }
 
+impl Update {
+    /// Update data with `status` checked to be a known [PriceStatus]. Prefer
+    /// this over building the struct directly.
+    pub fn new(price: i64, confidence: u64, status: u32) -> Result<Self, OracleError> {
+        PriceStatus::try_from(status)?;
+        Ok(Self {
+            price,
+            confidence,
+            status,
+        })
+    }
+}
+
+/// UpdateWide instruction data
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct UpdateWide {
+    /// full-width price used to update oracle data
+    pub price: i128,
+    /// confidence used to update oracle data
+    pub confidence: u64,
+    /// status used to update oracle data
+    pub status: u32,
+}
+
+/// Initialize instruction data
+#[repr(C)]
+#[derive(Debug, PartialEq)]
+pub struct Initialize {
+    /// confidence floor applied to updates
+    pub min_confidence: u64,
+    /// reject updates below the confidence floor instead of clamping them up
+    pub reject_below_min_confidence: bool,
+    /// feature toggles for the oracle
+    pub flags: OracleFlags,
+    /// decimals of the base token being priced
+    pub base_decimals: u8,
+    /// decimals of the quote token the price is denominated in
+    pub quote_decimals: u8,
+    /// number of highest and lowest components discarded by readers averaging
+    /// them, see `OracleState::trimmed_component_price`
+    pub aggregation_trim: u8,
+    /// price the ema starts from, weighted as one update; zero leaves it unseeded
+    pub initial_price: i64,
+    /// age in slots past which consumers should reject the price; zero for no limit
+    pub max_staleness_slots: u64,
+    /// slots after publication before a price becomes valid
+    pub valid_slot_delay: u64,
+    /// number of quoters making up the aggregate; must be at least one
+    pub num_quoters: u32,
+}
+
+/// Most samples an [UpdateBatchSameAccount] can carry, as its sample count is
+/// packed in a single byte.
+pub const MAX_BATCH_SAMPLES: usize = u8::MAX as usize;
+
+/// UpdateBatchSameAccount instruction data
+#[repr(C)]
+#[derive(Debug, PartialEq)]
+pub struct UpdateBatchSameAccount {
+    /// historical `(price, confidence, slot)` samples, oldest first; at most
+    /// [MAX_BATCH_SAMPLES] of them
+    pub samples: Vec<(i64, u64, u64)>,
+}
+
+impl UpdateBatchSameAccount {
+    /// Batch of `samples`, checked to fit in a packed instruction. Prefer this
+    /// over building the struct directly.
+    pub fn new(samples: Vec<(i64, u64, u64)>) -> Result<Self, OracleError> {
+        if samples.len() > MAX_BATCH_SAMPLES {
+            return Err(OracleError::InvalidInstruction);
+        }
+        Ok(Self { samples })
+    }
+}
+
+/// PublishComponent instruction data
+#[repr(C)]
+#[derive(Debug, PartialEq)]
+pub struct PublishComponent {
+    /// component slot being published
+    pub index: u8,
+    /// component price
+    pub price: i64,
+    /// component confidence
+    pub confidence: u64,
+}
+
+/// UpdatePctConfidence instruction data
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct UpdatePctConfidence {
+    /// price used to update oracle data
+    pub price: i64,
+    /// confidence in basis points of the price
+    pub confidence_bps: u32,
+    /// status used to update oracle data
+    pub status: u32,
+}
+
+/// UpdateQuote instruction data
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct UpdateQuote {
+    /// best bid, must not exceed the ask
+    pub bid: i64,
+    /// best ask
+    pub ask: i64,
+    /// status used to update oracle data
+    pub status: u32,
+}
+
+/// UpdateConfidence instruction data
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct UpdateConfidence {
+    /// confidence used to update oracle data
+    pub confidence: u64,
+    /// status used to update oracle data
+    pub status: u32,
+}
+
+/// AddPublisher instruction data
+#[repr(C)]
+#[derive(Debug, PartialEq)]
+pub struct AddPublisher {
+    /// component slot the publisher is allowed to publish
+    pub index: u8,
+    /// publisher added to the allowlist
+    pub publisher: Pubkey,
+}
+
+/// UpdateSigned instruction data
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct UpdateSigned {
+    /// update signed by the authority
+    pub update: Update,
+    /// slot the update was signed for, must be newer than the stored publish slot
+    pub slot: u64,
+}
+
+impl UpdateSigned {
+    /// Message the authority signs for `oracle`: the oracle key followed by the
+    /// little-endian price, confidence, status and slot.
+    pub fn message(&self, oracle: &Pubkey) -> Vec<u8> {
+        let mut buf = Vec::with_capacity(32 + 28);
+        buf.extend_from_slice(oracle.as_ref());
+        buf.extend_from_slice(&self.update.price.to_le_bytes());
+        buf.extend_from_slice(&self.update.confidence.to_le_bytes());
+        buf.extend_from_slice(&self.update.status.to_le_bytes());
+        buf.extend_from_slice(&self.slot.to_le_bytes());
+        buf
+    }
+}
+
+/// SetValidSlot instruction data
+#[cfg(feature = "test-helpers")]
+#[repr(C)]
+#[derive(Debug, PartialEq)]
+pub struct SetValidSlot {
+    /// valid slot written to oracle data
+    pub valid_slot: u64,
+    /// last slot written to oracle data
+    pub last_slot: u64,
+}
+
+/// UpdateWithSlot instruction data
+#[cfg(feature = "test-helpers")]
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct UpdateWithSlot {
+    /// update written to oracle data
+    pub update: Update,
+    /// slot recorded in place of the clock's
+    pub slot: u64,
+}
+
 /// Instructions supported by the update oracle program.
 #[repr(C)]
 #[derive(Debug, PartialEq)]
 pub enum OracleInstruction {
     ///   Oracle update..
+    ///
+    ///   0. `[writable, signer]` Oracle account.
+    ///   1. `[]` Program config PDA, checked for the global pause.
     Update(Update),
+    ///   Overwrite the valid and last slots of an oracle, used to simulate
+    ///   stale feeds in tests.
+    ///
+    ///   0. `[writable]` Oracle account.
+    ///   1. `[signer]` Authority.
+    #[cfg(feature = "test-helpers")]
+    SetValidSlot(SetValidSlot),
+    ///   Initialize an oracle account and its configuration. An account of
+    ///   `OracleV2::LEN` bytes is initialized with a full-width price.
+    ///
+    ///   0. `[writable, signer]` Oracle account.
+    ///   1. `[]` Authority allowed to sign updates on the oracle's behalf.
+    Initialize(Initialize),
+    ///   Oracle update signed by the stored authority.
+    ///
+    ///   0. `[writable]` Oracle account.
+    ///   1. `[signer]` Authority.
+    ///   2. `[]` Program config PDA, checked for the global pause.
+    UpdateWithAuthority(Update),
+    ///   Backfill the price history without changing the current aggregate.
+    ///
+    ///   0. `[writable]` Oracle account.
+    ///   1. `[signer]` Authority.
+    ///   2. `[]` Program config PDA, checked for the global pause.
+    UpdateBatchSameAccount(UpdateBatchSameAccount),
+    ///   Freeze an oracle ahead of migrating its data to a new program, so no
+    ///   further updates land.
+    ///
+    ///   0. `[writable]` Oracle account.
+    ///   1. `[signer]` Authority.
+    PrepareTransfer,
+    ///   Publish a component price on behalf of the publisher allowlisted for
+    ///   the component by [OracleInstruction::AddPublisher].
+    ///
+    ///   0. `[writable]` Oracle account.
+    ///   1. `[signer]` Authority.
+    ///   2. `[signer]` Publisher recorded against the component.
+    ///   3. `[]` Program config PDA, checked for the global pause.
+    PublishComponent(PublishComponent),
+    ///   Oracle update signed off-chain by the stored authority and submitted
+    ///   by any relayer. The instruction immediately before this one must be
+    ///   an ed25519 program instruction verifying the authority's signature
+    ///   over [UpdateSigned::message].
+    ///
+    ///   0. `[writable]` Oracle account.
+    ///   1. `[]` Instructions sysvar.
+    ///   2. `[]` Program config PDA, checked for the global pause.
+    UpdateSigned(UpdateSigned),
+    ///   Block updates program-wide, creating the config PDA on first use.
+    ///
+    ///   0. `[writable]` Program config PDA.
+    ///   1. `[writable, signer]` Program admin, pays for the config PDA.
+    ///   2. `[]` System program.
+    Pause,
+    ///   Allow updates again after a [OracleInstruction::Pause].
+    ///
+    ///   0. `[writable]` Program config PDA.
+    ///   1. `[writable, signer]` Program admin, pays for the config PDA.
+    ///   2. `[]` System program.
+    Unpause,
+    ///   Oracle update with the confidence given in basis points of the price
+    ///   rather than as an absolute value.
+    ///
+    ///   0. `[writable, signer]` Oracle account.
+    ///   1. `[]` Program config PDA, checked for the global pause.
+    UpdatePctConfidence(UpdatePctConfidence),
+    ///   Zero the oracle account so it reads as uninitialized again, keeping
+    ///   the account and its rent open for reuse. Fails once the oracle is
+    ///   frozen.
+    ///
+    ///   0. `[writable]` Oracle account.
+    ///   1. `[signer]` Authority.
+    ClearData,
+    ///   Allowlist a publisher for an empty component slot, counting it
+    ///   towards `num_quoters`.
+    ///
+    ///   0. `[writable]` Oracle account.
+    ///   1. `[signer]` Authority.
+    AddPublisher(AddPublisher),
+    ///   Remove the publisher of a component slot from the allowlist, clearing
+    ///   its component price. `num_quoters` is recounted but kept at one or
+    ///   more.
+    ///
+    ///   0. `[writable]` Oracle account.
+    ///   1. `[signer]` Authority.
+    RemovePublisher {
+        /// component slot whose publisher is removed
+        index: u8,
+    },
+    ///   Oracle update with a full-width price, for oracles initialized with
+    ///   the `OracleV2` layout.
+    ///
+    ///   0. `[writable, signer]` Oracle account.
+    ///   1. `[]` Program config PDA, checked for the global pause.
+    UpdateWide(UpdateWide),
+    ///   Attach an operator-defined metadata blob to the oracle.
+    ///
+    ///   0. `[writable]` Oracle account.
+    ///   1. `[signer]` Authority.
+    SetMetadata {
+        /// metadata stored on the oracle, such as the hash of a source URL
+        metadata: [u8; 32],
+    },
+    ///   Propose a new authority, which takes over once it signs an
+    ///   `AcceptAuthority`. Replaces any pending proposal.
+    ///
+    ///   0. `[writable]` Oracle account.
+    ///   1. `[signer]` Authority.
+    ProposeAuthority {
+        /// authority that may accept the handoff
+        new_authority: Pubkey,
+    },
+    ///   Complete a handoff started by `ProposeAuthority`, making the pending
+    ///   authority the oracle's authority.
+    ///
+    ///   0. `[writable]` Oracle account.
+    ///   1. `[signer]` Pending authority.
+    AcceptAuthority,
+    ///   Set the kind of value the feed publishes.
+    ///
+    ///   0. `[writable]` Oracle account.
+    ///   1. `[signer]` Authority.
+    SetPriceType {
+        /// new `PriceType` of the feed
+        price_type: u32,
+    },
+    ///   Oracle update from a bid and ask: the price is their mid and the
+    ///   confidence half the spread.
+    ///
+    ///   0. `[writable, signer]` Oracle account.
+    ///   1. `[]` Program config PDA, checked for the global pause.
+    UpdateQuote(UpdateQuote),
+    ///   Oracle update at a caller-supplied slot instead of the clock's, for
+    ///   deterministic tests. The timestamp is carried over from the previous
+    ///   update.
+    ///
+    ///   0. `[writable, signer]` Oracle account.
+    #[cfg(feature = "test-helpers")]
+    UpdateWithSlot(UpdateWithSlot),
+    ///   Oracle update that keeps the current price, changing only the
+    ///   confidence and status, e.g. to widen the confidence during
+    ///   uncertainty.
+    ///
+    ///   0. `[writable, signer]` Oracle account.
+    ///   1. `[]` Program config PDA, checked for the global pause.
+    UpdateConfidence(UpdateConfidence),
+}
+
+/// Checks that `input` carries a known tag followed by at least the number of
+/// bytes that variant expects, without constructing the instruction. Trailing
+/// bytes are ignored, as they are by [OracleInstruction::unpack].
+pub fn validate_instruction(input: &[u8]) -> Result<(), OracleError> {
+    let (&tag, rest) = input.split_first().ok_or(OracleError::InvalidInstruction)?;
+    let expected = match tag {
+        0 | 3 => 20,
+        #[cfg(feature = "test-helpers")]
+        1 => 16,
+        2 => 44,
+        4 => {
+            let (&count, _) = rest.split_first().ok_or(OracleError::InvalidInstruction)?;
+            1 + count as usize * 24
+        }
+        5 | 8 | 9 | 11 => 0,
+        6 => 17,
+        7 | 14 => 28,
+        10 => 16,
+        12 => 33,
+        13 => 1,
+        15 | 16 => 32,
+        17 => 0,
+        18 => 4,
+        19 => 20,
+        #[cfg(feature = "test-helpers")]
+        20 => 28,
+        21 => 12,
+        _ => return Err(OracleError::InvalidInstruction),
+    };
+    if rest.len() < expected {
+        return Err(OracleError::InvalidInstruction);
+    }
+    Ok(())
 }
 
 impl OracleInstruction {
@@ -44,6 +410,160 @@ impl OracleInstruction {
                     status,
                 })
             }
+            #[cfg(feature = "test-helpers")]
+            1 => {
+                let (valid_slot, rest) = Self::unpack_u64(rest)?;
+                let (last_slot, _rest) = Self::unpack_u64(rest)?;
+                Self::SetValidSlot(SetValidSlot {
+                    valid_slot,
+                    last_slot,
+                })
+            }
+            2 => {
+                let (min_confidence, rest) = Self::unpack_u64(rest)?;
+                let (reject_below_min_confidence, rest) = Self::unpack_bool(rest)?;
+                let (flags, rest) = Self::unpack_u32(rest)?;
+                let flags = OracleFlags::from_bits(flags).ok_or(OracleError::InvalidInstruction)?;
+                let (base_decimals, rest) = Self::unpack_u8(rest)?;
+                let (quote_decimals, rest) = Self::unpack_u8(rest)?;
+                let (aggregation_trim, rest) = Self::unpack_u8(rest)?;
+                let (initial_price, rest) = Self::unpack_i64(rest)?;
+                let (max_staleness_slots, rest) = Self::unpack_u64(rest)?;
+                let (valid_slot_delay, rest) = Self::unpack_u64(rest)?;
+                let (num_quoters, _rest) = Self::unpack_u32(rest)?;
+                Self::Initialize(Initialize {
+                    min_confidence,
+                    reject_below_min_confidence,
+                    flags,
+                    base_decimals,
+                    quote_decimals,
+                    aggregation_trim,
+                    initial_price,
+                    max_staleness_slots,
+                    valid_slot_delay,
+                    num_quoters,
+                })
+            }
+            3 => {
+                let (price, rest) = Self::unpack_i64(rest)?;
+                let (confidence, rest) = Self::unpack_u64(rest)?;
+                let (status, _rest) = Self::unpack_u32(rest)?;
+                Self::UpdateWithAuthority(Update {
+                    price,
+                    confidence,
+                    status,
+                })
+            }
+            4 => {
+                let (&count, mut rest) = rest.split_first().ok_or(OracleError::InvalidInstruction)?;
+                let mut samples = Vec::with_capacity(count as usize);
+                for _ in 0..count {
+                    let (price, next) = Self::unpack_i64(rest)?;
+                    let (confidence, next) = Self::unpack_u64(next)?;
+                    let (slot, next) = Self::unpack_u64(next)?;
+                    samples.push((price, confidence, slot));
+                    rest = next;
+                }
+                Self::UpdateBatchSameAccount(UpdateBatchSameAccount { samples })
+            }
+            5 => Self::PrepareTransfer,
+            8 => Self::Pause,
+            9 => Self::Unpause,
+            11 => Self::ClearData,
+            12 => {
+                let (index, rest) = Self::unpack_u8(rest)?;
+                let (publisher, _rest) = Self::unpack_pubkey(rest)?;
+                Self::AddPublisher(AddPublisher { index, publisher })
+            }
+            13 => {
+                let (index, _rest) = Self::unpack_u8(rest)?;
+                Self::RemovePublisher { index }
+            }
+            14 => {
+                let (price, rest) = Self::unpack_i128(rest)?;
+                let (confidence, rest) = Self::unpack_u64(rest)?;
+                let (status, _rest) = Self::unpack_u32(rest)?;
+                Self::UpdateWide(UpdateWide {
+                    price,
+                    confidence,
+                    status,
+                })
+            }
+            15 => {
+                let (metadata, _rest) = Self::unpack_pubkey(rest)?;
+                Self::SetMetadata {
+                    metadata: metadata.to_bytes(),
+                }
+            }
+            16 => {
+                let (new_authority, _rest) = Self::unpack_pubkey(rest)?;
+                Self::ProposeAuthority { new_authority }
+            }
+            17 => Self::AcceptAuthority,
+            18 => {
+                let (price_type, _rest) = Self::unpack_u32(rest)?;
+                Self::SetPriceType { price_type }
+            }
+            19 => {
+                let (bid, rest) = Self::unpack_i64(rest)?;
+                let (ask, rest) = Self::unpack_i64(rest)?;
+                let (status, _rest) = Self::unpack_u32(rest)?;
+                Self::UpdateQuote(UpdateQuote { bid, ask, status })
+            }
+            #[cfg(feature = "test-helpers")]
+            20 => {
+                let (price, rest) = Self::unpack_i64(rest)?;
+                let (confidence, rest) = Self::unpack_u64(rest)?;
+                let (status, rest) = Self::unpack_u32(rest)?;
+                let (slot, _rest) = Self::unpack_u64(rest)?;
+                Self::UpdateWithSlot(UpdateWithSlot {
+                    update: Update {
+                        price,
+                        confidence,
+                        status,
+                    },
+                    slot,
+                })
+            }
+            21 => {
+                let (confidence, rest) = Self::unpack_u64(rest)?;
+                let (status, _rest) = Self::unpack_u32(rest)?;
+                Self::UpdateConfidence(UpdateConfidence { confidence, status })
+            }
+            10 => {
+                let (price, rest) = Self::unpack_i64(rest)?;
+                let (confidence_bps, rest) = Self::unpack_u32(rest)?;
+                let (status, _rest) = Self::unpack_u32(rest)?;
+                Self::UpdatePctConfidence(UpdatePctConfidence {
+                    price,
+                    confidence_bps,
+                    status,
+                })
+            }
+            6 => {
+                let (index, rest) = Self::unpack_u8(rest)?;
+                let (price, rest) = Self::unpack_i64(rest)?;
+                let (confidence, _rest) = Self::unpack_u64(rest)?;
+                Self::PublishComponent(PublishComponent {
+                    index,
+                    price,
+                    confidence,
+                })
+            }
+            7 => {
+                let (price, rest) = Self::unpack_i64(rest)?;
+                let (confidence, rest) = Self::unpack_u64(rest)?;
+                let (status, rest) = Self::unpack_u32(rest)?;
+                let (slot, _rest) = Self::unpack_u64(rest)?;
+                Self::UpdateSigned(UpdateSigned {
+                    update: Update {
+                        price,
+                        confidence,
+                        status,
+                    },
+                    slot,
+                })
+            }
             _ => return Err(OracleError::InvalidInstruction.into()),
         })
     }
@@ -76,6 +596,20 @@ impl OracleInstruction {
         }
     }
 
+    fn unpack_i128(input: &[u8]) -> Result<(i128, &[u8]), ProgramError> {
+        if input.len() >= 16 {
+            let (value, rest) = input.split_at(16);
+            let value = value
+                .get(..16)
+                .and_then(|slice| slice.try_into().ok())
+                .map(i128::from_le_bytes)
+                .ok_or(OracleError::InvalidInstruction)?;
+            Ok((value, rest))
+        } else {
+            Err(OracleError::InvalidInstruction.into())
+        }
+    }
+
     fn unpack_u32(input: &[u8]) -> Result<(u32, &[u8]), ProgramError> {
         if input.len() >= 4 {
             let (value, rest) = input.split_at(4);
@@ -90,10 +624,37 @@ impl OracleInstruction {
         }
     }
 
+    fn unpack_pubkey(input: &[u8]) -> Result<(Pubkey, &[u8]), ProgramError> {
+        if input.len() >= 32 {
+            let (key, rest) = input.split_at(32);
+            let key = key
+                .try_into()
+                .map(Pubkey::new_from_array)
+                .map_err(|_| OracleError::InvalidInstruction)?;
+            Ok((key, rest))
+        } else {
+            Err(OracleError::InvalidInstruction.into())
+        }
+    }
+
+    fn unpack_u8(input: &[u8]) -> Result<(u8, &[u8]), ProgramError> {
+        let (&value, rest) = input.split_first().ok_or(OracleError::InvalidInstruction)?;
+        Ok((value, rest))
+    }
+
+    fn unpack_bool(input: &[u8]) -> Result<(bool, &[u8]), ProgramError> {
+        let (&value, rest) = input.split_first().ok_or(OracleError::InvalidInstruction)?;
+        match value {
+            0 => Ok((false, rest)),
+            1 => Ok((true, rest)),
+            _ => Err(OracleError::InvalidInstruction.into()),
+        }
+    }
+
     /// Packs a [OracleInstruction] into a byte buffer.
     pub fn pack(&self) -> Vec<u8> {
         let mut buf = Vec::with_capacity(size_of::<Self>());
-        match &*self {
+        match self {
             Self::Update(Update {
                 price,
                 confidence,
@@ -104,6 +665,151 @@ impl OracleInstruction {
                 buf.extend_from_slice(&confidence.to_le_bytes());
                 buf.extend_from_slice(&status.to_le_bytes());
             }
+            #[cfg(feature = "test-helpers")]
+            Self::SetValidSlot(SetValidSlot {
+                valid_slot,
+                last_slot,
+            }) => {
+                buf.push(1);
+                buf.extend_from_slice(&valid_slot.to_le_bytes());
+                buf.extend_from_slice(&last_slot.to_le_bytes());
+            }
+            Self::Initialize(Initialize {
+                min_confidence,
+                reject_below_min_confidence,
+                flags,
+                base_decimals,
+                quote_decimals,
+                aggregation_trim,
+                initial_price,
+                max_staleness_slots,
+                valid_slot_delay,
+                num_quoters,
+            }) => {
+                buf.push(2);
+                buf.extend_from_slice(&min_confidence.to_le_bytes());
+                buf.push(*reject_below_min_confidence as u8);
+                buf.extend_from_slice(&flags.bits().to_le_bytes());
+                buf.push(*base_decimals);
+                buf.push(*quote_decimals);
+                buf.push(*aggregation_trim);
+                buf.extend_from_slice(&initial_price.to_le_bytes());
+                buf.extend_from_slice(&max_staleness_slots.to_le_bytes());
+                buf.extend_from_slice(&valid_slot_delay.to_le_bytes());
+                buf.extend_from_slice(&num_quoters.to_le_bytes());
+            }
+            Self::UpdateWithAuthority(Update {
+                price,
+                confidence,
+                status,
+            }) => {
+                buf.push(3);
+                buf.extend_from_slice(&price.to_le_bytes());
+                buf.extend_from_slice(&confidence.to_le_bytes());
+                buf.extend_from_slice(&status.to_le_bytes());
+            }
+            Self::UpdateBatchSameAccount(UpdateBatchSameAccount { samples }) => {
+                buf.push(4);
+                // only the samples the one-byte count can encode, see
+                // [UpdateBatchSameAccount::new]
+                let count = samples.len().min(MAX_BATCH_SAMPLES);
+                buf.push(count as u8);
+                for (price, confidence, slot) in &samples[..count] {
+                    buf.extend_from_slice(&price.to_le_bytes());
+                    buf.extend_from_slice(&confidence.to_le_bytes());
+                    buf.extend_from_slice(&slot.to_le_bytes());
+                }
+            }
+            Self::PrepareTransfer => buf.push(5),
+            Self::Pause => buf.push(8),
+            Self::Unpause => buf.push(9),
+            Self::ClearData => buf.push(11),
+            Self::AddPublisher(AddPublisher { index, publisher }) => {
+                buf.push(12);
+                buf.push(*index);
+                buf.extend_from_slice(publisher.as_ref());
+            }
+            Self::RemovePublisher { index } => {
+                buf.push(13);
+                buf.push(*index);
+            }
+            Self::UpdateWide(UpdateWide {
+                price,
+                confidence,
+                status,
+            }) => {
+                buf.push(14);
+                buf.extend_from_slice(&price.to_le_bytes());
+                buf.extend_from_slice(&confidence.to_le_bytes());
+                buf.extend_from_slice(&status.to_le_bytes());
+            }
+            Self::SetMetadata { metadata } => {
+                buf.push(15);
+                buf.extend_from_slice(metadata);
+            }
+            Self::ProposeAuthority { new_authority } => {
+                buf.push(16);
+                buf.extend_from_slice(new_authority.as_ref());
+            }
+            Self::AcceptAuthority => buf.push(17),
+            Self::SetPriceType { price_type } => {
+                buf.push(18);
+                buf.extend_from_slice(&price_type.to_le_bytes());
+            }
+            Self::UpdateQuote(UpdateQuote { bid, ask, status }) => {
+                buf.push(19);
+                buf.extend_from_slice(&bid.to_le_bytes());
+                buf.extend_from_slice(&ask.to_le_bytes());
+                buf.extend_from_slice(&status.to_le_bytes());
+            }
+            #[cfg(feature = "test-helpers")]
+            Self::UpdateWithSlot(UpdateWithSlot {
+                update:
+                    Update {
+                        price,
+                        confidence,
+                        status,
+                    },
+                slot,
+            }) => {
+                buf.push(20);
+                buf.extend_from_slice(&price.to_le_bytes());
+                buf.extend_from_slice(&confidence.to_le_bytes());
+                buf.extend_from_slice(&status.to_le_bytes());
+                buf.extend_from_slice(&slot.to_le_bytes());
+            }
+            Self::UpdateConfidence(UpdateConfidence { confidence, status }) => {
+                buf.push(21);
+                buf.extend_from_slice(&confidence.to_le_bytes());
+                buf.extend_from_slice(&status.to_le_bytes());
+            }
+            Self::UpdatePctConfidence(UpdatePctConfidence {
+                price,
+                confidence_bps,
+                status,
+            }) => {
+                buf.push(10);
+                buf.extend_from_slice(&price.to_le_bytes());
+                buf.extend_from_slice(&confidence_bps.to_le_bytes());
+                buf.extend_from_slice(&status.to_le_bytes());
+            }
+            Self::PublishComponent(PublishComponent {
+                index,
+                price,
+                confidence,
+            }) => {
+                buf.push(6);
+                buf.push(*index);
+                buf.extend_from_slice(&price.to_le_bytes());
+                buf.extend_from_slice(&confidence.to_le_bytes());
+            }
+            Self::UpdateSigned(UpdateSigned { update, slot }) => {
+                buf.push(7);
+                buf.extend_from_slice(&update.price.to_le_bytes());
+                buf.extend_from_slice(&update.confidence.to_le_bytes());
+                buf.extend_from_slice(&update.status.to_le_bytes());
+                buf.extend_from_slice(&slot.to_le_bytes());
+            }
         }
         buf
     }
@@ -2,8 +2,11 @@
 
 //! An Uniswap-like program for the Solana blockchain.
 
+pub mod client;
+pub mod consts;
 pub mod error;
 pub mod instruction;
+pub mod math;
 pub mod processor;
 pub mod state;
 
@@ -16,3 +19,8 @@ pub use solana_program;
 extern crate arrayref;
 
 solana_program::declare_id!("8BR3zs8zSXetpnDjCtHWnkpSkNSydWb3PTTDuVKku2uu");
+
+/// Program admin allowed to pause and unpause updates globally.
+pub mod admin {
+    solana_program::declare_id!("yiiXgCpcduHGgPmMYuAkPd1sCYdZzvmRRq4mEYDXwAQ");
+}
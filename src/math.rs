@@ -0,0 +1,45 @@
+//! Checked arithmetic helpers shared by the processor and the state readers
+
+use crate::error::OracleError;
+
+/// Add, failing with [OracleError::ArithmeticError] on overflow.
+pub fn checked_add(a: i128, b: i128) -> Result<i128, OracleError> {
+    a.checked_add(b).ok_or(OracleError::ArithmeticError)
+}
+
+/// Subtract, failing with [OracleError::ArithmeticError] on overflow.
+pub fn checked_sub(a: i128, b: i128) -> Result<i128, OracleError> {
+    a.checked_sub(b).ok_or(OracleError::ArithmeticError)
+}
+
+/// Multiply, failing with [OracleError::ArithmeticError] on overflow.
+pub fn checked_mul(a: i128, b: i128) -> Result<i128, OracleError> {
+    a.checked_mul(b).ok_or(OracleError::ArithmeticError)
+}
+
+/// Divide, failing with [OracleError::ArithmeticError] on division by zero or
+/// overflow.
+pub fn checked_div(a: i128, b: i128) -> Result<i128, OracleError> {
+    a.checked_div(b).ok_or(OracleError::ArithmeticError)
+}
+
+/// Compute `a * b / c`, failing with [OracleError::ArithmeticError] if the
+/// product overflows or `c` is zero.
+pub fn checked_mul_div(a: u128, b: u128, c: u128) -> Result<u128, OracleError> {
+    a.checked_mul(b)
+        .and_then(|product| product.checked_div(c))
+        .ok_or(OracleError::ArithmeticError)
+}
+
+/// `value * 10^exponent`, or `None` if the power of ten or the product
+/// overflows, or if a negative exponent scales a nonzero value down to zero.
+pub fn apply_exponent(value: i128, exponent: i32) -> Option<i128> {
+    let scale = 10i128.checked_pow(exponent.unsigned_abs())?;
+    if exponent >= 0 {
+        return value.checked_mul(scale);
+    }
+    match value / scale {
+        0 if value != 0 => None,
+        scaled => Some(scaled),
+    }
+}
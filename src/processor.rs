@@ -2,46 +2,427 @@
 use crate::{
     error::OracleError,
     instruction::{
-        Update, OracleInstruction,
+        validate_instruction, AddPublisher, Initialize, OracleInstruction, PublishComponent,
+        Update, UpdateBatchSameAccount, UpdateConfidence, UpdatePctConfidence, UpdateQuote,
+        UpdateSigned, UpdateWide,
+    },
+    math::{checked_add, checked_div, checked_mul, checked_mul_div, checked_sub},
+    state::{
+        find_config_address, OracleFlags, OracleState, OracleV1, OracleV2, OracleVersion,
+        PreviousUpdate, PriceStatus, PriceType, ProgramConfig, AGGREGATION_TRIM_SLOT,
+        BPS_DENOMINATOR, CONFIG_SEED, MAX_COMPONENTS, MAX_STALENESS_SLOT, MIN_CONFIDENCE_SLOT,
+        QUOTE_SLOT, REJECT_BELOW_MIN_CONFIDENCE_SLOT, SEQUENCE_SLOT, UPDATE_COUNT_SLOT,
+        VALID_SLOT_DELAY_SLOT,
     },
-    state::{OracleV1, OracleVersion},
 };
+#[cfg(feature = "test-helpers")]
+use crate::instruction::{SetValidSlot, UpdateWithSlot};
+use arrayref::{array_ref, array_refs};
 use num_traits::{FromPrimitive};
 use solana_program::{
-    account_info::{next_account_info, AccountInfo},
+    account_info::AccountInfo,
     decode_error::DecodeError,
     entrypoint::ProgramResult,
     msg,
     program_error::{PrintProgramError, ProgramError},
+    program_pack::Pack,
     pubkey::Pubkey,
     clock::Clock,
-    sysvar::Sysvar,
+    ed25519_program,
+    instruction::Instruction,
+    program::invoke_signed,
+    rent::Rent,
+    system_instruction,
+    sysvar::{instructions, Sysvar},
 };
+use std::convert::TryFrom;
+
+// re-exported so existing `processor::MAGIC`-style paths keep working
+pub use crate::consts::*;
+
+/// Weight of a new ema sample, proportional to the slots elapsed since the
+/// previous update so that bursts of updates don't dominate the average.
+fn ema_weight(slot_delta: u64) -> Result<u64, OracleError> {
+    EMA_WEIGHT
+        .checked_mul(slot_delta.clamp(1, EMA_MAX_SLOT_DELTA))
+        .ok_or(OracleError::ArithmeticError)
+}
+
+/// Folds one weighted sample into an ema numerator/denominator pair. Older
+/// samples decay by 1/EMA_PERIOD per step. The denominator is divided by a
+/// common factor whenever it passes the threshold, and shrunk further if the
+/// numerator no longer fits in its u64 field, with the numerator rescaled to
+/// match so the ratio survives arbitrarily long-lived accounts.
+fn ema_step(
+    numerator: i128,
+    denominator: u64,
+    sample: i128,
+    weight: u64,
+    max_numerator: i128,
+) -> Result<(i128, u64), OracleError> {
+    let denominator = denominator as i128;
+    let decayed = checked_sub(denominator, checked_div(denominator, EMA_PERIOD as i128)?)?;
+    let numerator = if denominator == 0 {
+        0
+    } else {
+        rescale(numerator, denominator, decayed)?
+    };
+    let weight = weight as i128;
+    let mut numerator = checked_add(numerator, checked_mul(sample, weight)?)?;
+    let mut denominator = checked_add(decayed, weight)?;
+    while denominator > EMA_RENORMALIZE_THRESHOLD as i128 {
+        let reduced = checked_div(denominator, EMA_RENORMALIZE_FACTOR as i128)?;
+        numerator = rescale(numerator, denominator, reduced)?;
+        denominator = reduced;
+    }
+    if numerator.abs() > max_numerator {
+        // the largest denominator whose numerator fits, down to the bare average
+        let average = checked_div(numerator, denominator)?;
+        let reduced = (max_numerator / (average.abs() + 1)).clamp(1, denominator);
+        numerator = rescale(numerator, denominator, reduced)?.clamp(-max_numerator, max_numerator);
+        denominator = reduced;
+    }
+    Ok((numerator, denominator as u64))
+}
+
+/// `numerator * new_denominator / denominator`, rounded towards zero, without
+/// forming the full product.
+fn rescale(numerator: i128, denominator: i128, new_denominator: i128) -> Result<i128, OracleError> {
+    let whole = checked_mul(checked_div(numerator, denominator)?, new_denominator)?;
+    let part = checked_div(checked_mul(numerator % denominator, new_denominator)?, denominator)?;
+    checked_add(whole, part)
+}
+
+/// Absolute confidence for a confidence given in basis points of `price`.
+fn confidence_from_bps(price: i64, confidence_bps: u32) -> Result<u64, OracleError> {
+    let confidence = checked_mul_div(
+        price.unsigned_abs() as u128,
+        confidence_bps as u128,
+        BPS_DENOMINATOR as u128,
+    )?;
+    u64::try_from(confidence).map_err(|_| OracleError::ArithmeticError)
+}
+
+/// Fails if `index` is beyond the oracle's component prices or the buffer
+/// slots reserved for them.
+fn check_component_index(oracle: &dyn OracleState, index: u8) -> ProgramResult {
+    if index as u32 >= oracle.active_component_count() {
+        return Err(OracleError::ComponentIndexOutOfRange.into());
+    }
+    Ok(())
+}
+
+/// Fails if the oracle has been frozen by a `PrepareTransfer`.
+fn require_not_frozen(oracle: &dyn OracleState) -> ProgramResult {
+    if oracle.flags().contains(OracleFlags::FROZEN) {
+        return Err(OracleError::AccountFrozen.into());
+    }
+    Ok(())
+}
+
+/// The [OracleV1] fields of an oracle account, without checking that it's
+/// initialized. Accounts longer than [OracleV1::LEN] hold them as a prefix,
+/// whether the rest is padding or the wide price of an [OracleV2].
+fn unpack_base(data: &[u8]) -> Result<OracleV1, ProgramError> {
+    OracleV1::unpack_unchecked(data.get(..OracleV1::LEN).unwrap_or(data))
+}
+
+/// Next account of the instruction, logging which one is missing before
+/// failing with `NotEnoughAccountKeys`.
+fn next_account<'a, 'b, I: Iterator<Item = &'a AccountInfo<'b>>>(
+    iter: &mut I,
+    name: &str,
+) -> Result<&'a AccountInfo<'b>, ProgramError> {
+    iter.next().ok_or_else(|| {
+        msg!("Missing {} account", name);
+        ProgramError::NotEnoughAccountKeys
+    })
+}
+
+/// Fails with `IncorrectSigner` unless `info` signed the transaction.
+pub fn require_signer(info: &AccountInfo) -> Result<(), ProgramError> {
+    if !info.is_signer {
+        return Err(OracleError::IncorrectSigner.into());
+    }
+    Ok(())
+}
+
+/// Fails with `IncorrectProgramId` unless `info` is owned by `program_id`.
+pub fn require_owner(info: &AccountInfo, program_id: &Pubkey) -> Result<(), ProgramError> {
+    if info.owner != program_id {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+    Ok(())
+}
+
+/// Fails with `AccountNotWritable` unless `info` was passed as writable.
+pub fn require_writable(info: &AccountInfo) -> Result<(), ProgramError> {
+    if !info.is_writable {
+        return Err(OracleError::AccountNotWritable.into());
+    }
+    Ok(())
+}
+
+/// Derives the oracle state after applying `update` at the given clock values.
+/// `existing` is the account's current state, or `None` if it has never been
+/// written, in which case `key` becomes the product account key. Also returns
+/// whether the update was too uncertain to trust, in which case the prior
+/// price, its slots, timestamp and ema are kept and no history is recorded.
+/// The update handlers fail such an update with `UntrustedPrice` rather than
+/// write it, leaving the last price to age out through `valid_slot`.
+pub fn build_state(
+    key: &Pubkey,
+    existing: Option<&OracleV1>,
+    update: &Update,
+    slot: u64,
+    timestamp: i64,
+) -> Result<(OracleV1, bool), ProgramError> {
+    derive_state(key, existing, update, update.price.unsigned_abs() as u128, slot, timestamp)
+}
+
+/// Like [build_state] for a full-width price. The V1 fields carry the price
+/// saturated to the `i64` range, while whether it's trusted is decided on
+/// the full `price`.
+pub fn build_wide_state(
+    key: &Pubkey,
+    existing: &OracleV1,
+    price: i128,
+    confidence: u64,
+    status: u32,
+    slot: u64,
+    timestamp: i64,
+) -> Result<(OracleV1, bool), ProgramError> {
+    let update = Update {
+        price: price.clamp(i64::MIN as i128 + 1, i64::MAX as i128) as i64,
+        confidence,
+        status,
+    };
+    derive_state(key, Some(existing), &update, price.unsigned_abs(), slot, timestamp)
+}
+
+/// Shared body of [build_state] and [build_wide_state], with `magnitude` the
+/// absolute value of the price the update's confidence is measured against.
+fn derive_state(
+    key: &Pubkey,
+    existing: Option<&OracleV1>,
+    update: &Update,
+    magnitude: u128,
+    slot: u64,
+    timestamp: i64,
+) -> Result<(OracleV1, bool), ProgramError> {
+    let zeroed;
+    let prior = match existing {
+        Some(existing) => existing,
+        None => {
+            // a never-written account is all zeros
+            zeroed = OracleV1::unpack_unchecked(&[0; OracleV1::LEN])?;
+            &zeroed
+        }
+    };
+    if existing.is_some() && prior.acctype != ATYPE {
+        msg!("Oracle account has already been initialized as a different account type");
+        return Err(ProgramError::InvalidAccountData);
+    }
+
+    require_not_frozen(prior)?;
+
+    if matches!(existing, Some(existing) if timestamp < existing.timestamp) {
+        return Err(OracleError::TimestampOutOfRange.into());
+    }
+
+    let Update {
+        price,
+        confidence,
+        status,
+    } = *update;
+
+    // i64::MIN can't be negated or have its absolute value taken
+    if price == i64::MIN || (price == 0 && prior.flags().contains(OracleFlags::REJECT_ZERO_PRICE)) {
+        return Err(OracleError::InvalidPrice.into());
+    }
+
+    let min_confidence = prior.min_confidence();
+    let confidence = if confidence >= min_confidence {
+        confidence
+    } else if prior.reject_below_min_confidence() {
+        return Err(OracleError::ConfidenceBelowMinimum.into());
+    } else {
+        min_confidence
+    };
+
+    // a confidence interval as wide as the price itself makes the price
+    // untrustworthy: keep serving the last price, flagged as not trading
+    let untrusted = confidence as u128 >= magnitude;
+    let retained = untrusted && prior.has_price();
+    let (price, confidence, status) = if retained {
+        (prior.price_component, prior.confidence_component, PriceStatus::Unknown.into())
+    } else if untrusted {
+        (price, confidence, PriceStatus::Unknown.into())
+    } else {
+        (price, confidence, status)
+    };
+    let (last_slot, valid_slot, publish_slot, timestamp) = if retained {
+        (prior.last_slot, prior.valid_slot, prior.publish_slot, prior.timestamp)
+    } else {
+        (slot, slot.saturating_add(prior.valid_slot_delay()), slot, timestamp)
+    };
+
+    // the first update after initialize weighs as a single slot, so a seeded
+    // ema isn't swamped by the slots elapsed since genesis
+    let (slot_delta, product_account_key) = match existing {
+        Some(existing) if existing.last_slot != 0 => {
+            (slot.saturating_sub(existing.last_slot), existing.product_account_key)
+        }
+        Some(existing) => (1, existing.product_account_key),
+        None => (1, *key),
+    };
+    let (
+        ema_price_value,
+        ema_price_numerator,
+        ema_price_denominator,
+        ema_confidence_value,
+        ema_confidence_numerator,
+        ema_confidence_denominator,
+    ) = if retained {
+        (
+            prior.ema_price_value,
+            prior.ema_price_numerator,
+            prior.ema_price_denominator,
+            prior.ema_confidence_value,
+            prior.ema_confidence_numerator,
+            prior.ema_confidence_denominator,
+        )
+    } else if prior.flags().contains(OracleFlags::EMA_ENABLED) {
+        let weight = ema_weight(slot_delta)?;
+        let (price_numerator, price_denominator) = ema_step(
+            prior.ema_price_numerator as i64 as i128,
+            prior.ema_price_denominator,
+            price as i128,
+            weight,
+            i64::MAX as i128,
+        )?;
+        let (confidence_numerator, confidence_denominator) = ema_step(
+            prior.ema_confidence_numerator as i128,
+            prior.ema_confidence_denominator,
+            confidence as i128,
+            weight,
+            u64::MAX as i128,
+        )?;
+        (
+            checked_div(price_numerator, price_denominator as i128)? as i64 as u64,
+            price_numerator as i64 as u64,
+            price_denominator,
+            checked_div(confidence_numerator, confidence_denominator as i128)? as u64,
+            confidence_numerator as u64,
+            confidence_denominator,
+        )
+    } else {
+        (price as u64, NUMERATOR, DENOMINATOR, confidence, NUMERATOR, DENOMINATOR)
+    };
+
+    // the previous_* fields track the last update that was trading
+    let previous = if prior.status == u32::from(PriceStatus::Trading) {
+        PreviousUpdate {
+            slot: prior.valid_slot,
+            price: prior.price_component,
+            confidence: prior.confidence_component,
+            timestamp: prior.timestamp,
+        }
+    } else {
+        prior.previous()
+    };
+
+    let mut flags = prior.flags();
+    flags.insert(OracleFlags::HAS_PRICE);
+    let mut oracle = OracleV1 {
+        magic: MAGIC,
+        version: VERSION,
+        acctype: ATYPE,
+        size: SIZE,
+        price_type: existing.map_or(TYPE, |existing| existing.price_type),
+        exponent: EXPONENT,
+        num_component_prices: NUM_COMPONENT,
+        num_quoters: existing.map_or(NUM_QUOTERS, |existing| existing.num_quoters),
+        last_slot,
+        valid_slot,
+        ema_price_value,
+        ema_price_numerator,
+        ema_price_denominator,
+        ema_confidence_value,
+        ema_confidence_numerator,
+        ema_confidence_denominator,
+        timestamp,
+        min_publishers: MIN_PUBLISHERS,
+        drv2: prior.drv2,
+        drv3: prior.drv3,
+        drv4: flags.bits() as i32,
+        product_account_key,
+        next_price_account_key: prior.next_price_account_key,
+        previous_slot: previous.slot,
+        previous_price_component: previous.price,
+        previous_confidence_component: previous.confidence,
+        previous_timestamp: previous.timestamp,
+        price_component: price,
+        confidence_component: confidence,
+        status,
+        corporate_action: ACTION,
+        publish_slot,
+        buffer: prior.buffer,
+    };
+    let sequence = prior
+        .sequence()
+        .checked_add(1)
+        .ok_or(OracleError::ArithmeticError)?;
+    oracle.buffer[SEQUENCE_SLOT] = sequence as u128;
+    oracle.buffer[UPDATE_COUNT_SLOT] = prior.update_count().saturating_add(1) as u128;
+    if !retained && !prior.flags().contains(OracleFlags::SKIP_BUFFER) {
+        oracle.push_history(price, confidence, slot);
+    }
+    Ok((oracle, retained))
+}
+
+/// Size of the offsets record following the signature count in ed25519
+/// program instruction data.
+const ED25519_OFFSETS_LEN: usize = 14;
 
-/// magic number.
-pub const MAGIC: u32 = 0xa1b2c3d4;
-/// program version.
-pub const VERSION: u32 = 2;
-/// account type.
-pub const ATYPE: u32 = 3;
-/// account size.
-pub const SIZE: u32 = 3312;
-/// price type.
-pub const TYPE: u32 = 1;
-/// price exponent.
-pub const EXPONENT: i32 = -8;
-/// numerator state.
-pub const NUMERATOR: u64 = 0;
-/// denominator state.
-pub const DENOMINATOR: u64 = 0;
-/// number of quoters that make up aggregate.
-pub const NUM_COMPONENT: u32 = 10;
-/// slot of last valid aggregate price.
-pub const NUM_QUOTERS: u32 = 1;
-/// min publishers for valid price.
-pub const MIN_PUBLISHERS: u8 = 1;
-/// notification of any corporate action.
-pub const ACTION: u32 = 0;
+/// Checks that `ix` is an ed25519 program instruction verifying exactly one
+/// signature by `pubkey` over `message`, with all of it carried in `ix` itself.
+pub fn verify_ed25519_instruction(ix: &Instruction, pubkey: &Pubkey, message: &[u8]) -> ProgramResult {
+    if !ed25519_program::check_id(&ix.program_id) || !ix.accounts.is_empty() {
+        return Err(OracleError::InvalidSignature.into());
+    }
+    let data = &ix.data;
+    if data.len() < 2 + ED25519_OFFSETS_LEN || data[0] != 1 {
+        return Err(OracleError::InvalidSignature.into());
+    }
+    let offsets = array_ref![data, 2, ED25519_OFFSETS_LEN];
+    let (
+        _signature_offset,
+        signature_instruction_index,
+        public_key_offset,
+        public_key_instruction_index,
+        message_data_offset,
+        message_data_size,
+        message_instruction_index,
+    ) = array_refs![offsets, 2, 2, 2, 2, 2, 2, 2];
+    let this_instruction = u16::MAX.to_le_bytes();
+    if *signature_instruction_index != this_instruction
+        || *public_key_instruction_index != this_instruction
+        || *message_instruction_index != this_instruction
+    {
+        return Err(OracleError::InvalidSignature.into());
+    }
+
+    let public_key_offset = u16::from_le_bytes(*public_key_offset) as usize;
+    let message_data_offset = u16::from_le_bytes(*message_data_offset) as usize;
+    let message_data_size = u16::from_le_bytes(*message_data_size) as usize;
+    let signed_pubkey = data.get(public_key_offset..public_key_offset + 32);
+    let signed_message = data.get(message_data_offset..message_data_offset + message_data_size);
+    if signed_pubkey != Some(pubkey.as_ref()) || signed_message != Some(message) {
+        return Err(OracleError::InvalidSignature.into());
+    }
+    Ok(())
+}
 
 /// Program state handler.
 pub struct Processor {}
@@ -54,22 +435,643 @@ impl Processor {
         status: u32,
         accounts: &[AccountInfo],
     ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let data_account_info = next_account(account_info_iter, "oracle")?;
+
+        require_owner(data_account_info, program_id)?;
+        require_signer(data_account_info)?;
+        require_writable(data_account_info)?;
+
+        Self::write_update(data_account_info, price, confidence, status)
+    }
 
+    /// Processes an [UpdateWide].
+    pub fn process_update_wide(
+        program_id: &Pubkey,
+        price: i128,
+        confidence: u64,
+        status: u32,
+        accounts: &[AccountInfo],
+    ) -> ProgramResult {
         let account_info_iter = &mut accounts.iter();
-        let data_account_info = next_account_info(account_info_iter)?;
+        let data_account_info = next_account(account_info_iter, "oracle")?;
+
+        require_owner(data_account_info, program_id)?;
+        require_signer(data_account_info)?;
+        require_writable(data_account_info)?;
 
-        if data_account_info.owner != program_id {
-            return Err(ProgramError::IncorrectProgramId);
+        Self::write_wide_update(data_account_info, price, confidence, status)
+    }
+
+    /// Writes a full-width price into an [OracleV2] account whose signer
+    /// checks have already passed.
+    fn write_wide_update(
+        data_account_info: &AccountInfo,
+        price: i128,
+        confidence: u64,
+        status: u32,
+    ) -> ProgramResult {
+        let mut data = data_account_info.data.borrow_mut();
+        let existing = OracleV2::unpack_unchecked(&data)?;
+        if existing.base.magic != MAGIC || existing.base.version != VERSION_V2 {
+            return Err(ProgramError::UninitializedAccount);
         }
-        if !data_account_info.is_signer {
-           return Err(OracleError::IncorrectSigner.into());
+
+        let clock = Clock::get().unwrap();
+        let (mut base, retained) = build_wide_state(
+            data_account_info.key,
+            &existing.base,
+            price,
+            confidence,
+            status,
+            clock.slot,
+            clock.unix_timestamp,
+        )?;
+        if retained {
+            return Err(OracleError::UntrustedPrice.into());
         }
+        base.version = VERSION_V2;
+        base.size = SIZE_V2;
+        OracleVersion::pack(OracleVersion::OracleV2(OracleV2 { base, price }), &mut data)?;
+        Ok(())
+    }
 
+    /// Processes an [Update] signed by the oracle's stored authority rather
+    /// than by the data account itself.
+    pub fn process_update_with_authority(
+        program_id: &Pubkey,
+        price: i64,
+        confidence: u64,
+        status: u32,
+        accounts: &[AccountInfo],
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let data_account_info = next_account(account_info_iter, "oracle")?;
+        let authority_info = next_account(account_info_iter, "authority")?;
+
+        require_owner(data_account_info, program_id)?;
+        require_writable(data_account_info)?;
+        Self::check_authority(data_account_info, authority_info)?;
+
+        Self::write_update(data_account_info, price, confidence, status)
+    }
+
+    /// Processes an [UpdateQuote].
+    pub fn process_update_quote(
+        program_id: &Pubkey,
+        bid: i64,
+        ask: i64,
+        status: u32,
+        accounts: &[AccountInfo],
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let data_account_info = next_account(account_info_iter, "oracle")?;
+
+        require_owner(data_account_info, program_id)?;
+        require_signer(data_account_info)?;
+        require_writable(data_account_info)?;
+        if bid > ask {
+            return Err(OracleError::InvalidPrice.into());
+        }
+
+        // both fit: the mid lies between bid and ask, and half of any i64
+        // spread is below 2^63
+        let mid = ((bid as i128 + ask as i128) / 2) as i64;
+        let half_spread = ((ask as i128 - bid as i128) / 2) as u64;
+        Self::write_update_with_quote(data_account_info, mid, half_spread, status, Some((bid, ask)))
+    }
+
+    /// Processes an [UpdateConfidence].
+    pub fn process_update_confidence(
+        program_id: &Pubkey,
+        confidence: u64,
+        status: u32,
+        accounts: &[AccountInfo],
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let data_account_info = next_account(account_info_iter, "oracle")?;
+
+        require_owner(data_account_info, program_id)?;
+        require_signer(data_account_info)?;
+        require_writable(data_account_info)?;
+
+        let existing = unpack_base(&data_account_info.data.borrow())?;
+        if existing.magic != MAGIC {
+            return Err(ProgramError::UninitializedAccount);
+        }
+        if existing.version == VERSION_V2 {
+            // keep the full-width price rather than its saturated base field
+            let price = OracleV2::unpack_unchecked(&data_account_info.data.borrow())?.price;
+            return Self::write_wide_update(data_account_info, price, confidence, status);
+        }
+        Self::write_update(data_account_info, existing.price_component, confidence, status)
+    }
+
+    /// Processes an [UpdateSigned].
+    pub fn process_update_signed(
+        program_id: &Pubkey,
+        update_signed: UpdateSigned,
+        accounts: &[AccountInfo],
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let data_account_info = next_account(account_info_iter, "oracle")?;
+        let instructions_info = next_account(account_info_iter, "instructions sysvar")?;
+
+        require_owner(data_account_info, program_id)?;
+        require_writable(data_account_info)?;
+        if !instructions::check_id(instructions_info.key) {
+            return Err(ProgramError::InvalidArgument);
+        }
+
+        let existing = unpack_base(&data_account_info.data.borrow())?;
+        if existing.magic != MAGIC {
+            return Err(ProgramError::UninitializedAccount);
+        }
+        // every update that lands advances publish_slot to the clock's slot, at
+        // or past the signed one, and one that would keep the prior price fails
+        // instead, so a payload can land at most once
         let clock = Clock::get().unwrap();
-        let solana_slot = clock.slot;
-        let solana_timestamp = clock.unix_timestamp;
+        if update_signed.slot <= existing.publish_slot || update_signed.slot > clock.slot {
+            return Err(OracleError::InvalidSignature.into());
+        }
+
+        let current_index = instructions::load_current_index_checked(instructions_info)?;
+        if current_index == 0 {
+            return Err(OracleError::InvalidSignature.into());
+        }
+        let ed25519_ix =
+            instructions::load_instruction_at_checked(current_index as usize - 1, instructions_info)?;
+        verify_ed25519_instruction(
+            &ed25519_ix,
+            &existing.authority(),
+            &update_signed.message(data_account_info.key),
+        )?;
+
+        let Update {
+            price,
+            confidence,
+            status,
+        } = update_signed.update;
+        Self::write_update(data_account_info, price, confidence, status)
+    }
+
+    /// Checks that `authority_info` signed and matches the authority stored in
+    /// the oracle account.
+    fn check_authority(data_account_info: &AccountInfo, authority_info: &AccountInfo) -> ProgramResult {
+        require_signer(authority_info)?;
+        let existing = unpack_base(&data_account_info.data.borrow())?;
+        if existing.magic != MAGIC {
+            return Err(ProgramError::UninitializedAccount);
+        }
+        if existing.authority() != *authority_info.key {
+            return Err(OracleError::IncorrectAuthority.into());
+        }
+        Ok(())
+    }
+
+    /// Writes a new price into an oracle account whose signer checks have
+    /// already passed.
+    fn write_update(
+        data_account_info: &AccountInfo,
+        price: i64,
+        confidence: u64,
+        status: u32,
+    ) -> ProgramResult {
+        Self::write_update_with_quote(data_account_info, price, confidence, status, None)
+    }
+
+    /// Like [Processor::write_update], also recording the `(bid, ask)` the
+    /// price was derived from when given.
+    fn write_update_with_quote(
+        data_account_info: &AccountInfo,
+        price: i64,
+        confidence: u64,
+        status: u32,
+        quote: Option<(i64, i64)>,
+    ) -> ProgramResult {
+        let clock = Clock::get().unwrap();
+        Self::write_update_at(
+            data_account_info,
+            &Update {
+                price,
+                confidence,
+                status,
+            },
+            quote,
+            clock.slot,
+            clock.unix_timestamp,
+        )
+    }
+
+    /// Like [Processor::write_update_with_quote], at the given clock values.
+    fn write_update_at(
+        data_account_info: &AccountInfo,
+        update: &Update,
+        quote: Option<(i64, i64)>,
+        slot: u64,
+        timestamp: i64,
+    ) -> ProgramResult {
+        let existing = unpack_base(&data_account_info.data.borrow())?;
+        let existing = if existing.magic == MAGIC {
+            Some(&existing)
+        } else {
+            None
+        };
+
+        let wide = data_account_info.data_len() == OracleV2::LEN
+            && matches!(existing, Some(existing) if existing.version == VERSION_V2);
+
+        let (mut oracle, retained) =
+            build_state(data_account_info.key, existing, update, slot, timestamp)?;
+        if retained {
+            return Err(OracleError::UntrustedPrice.into());
+        }
+        if let Some((bid, ask)) = quote {
+            oracle.set_quote(bid, ask);
+        }
+
+        let mut data = data_account_info.data.borrow_mut();
+        if wide {
+            // an OracleV2 stays one, its full-width price following the update
+            oracle.version = VERSION_V2;
+            oracle.size = SIZE_V2;
+            OracleV2::pack_price_into_slice(update.price as i128, &mut data);
+        }
+        if oracle.flags().contains(OracleFlags::SKIP_BUFFER) {
+            oracle.pack_header_into_slice(&mut data);
+            for &index in [SEQUENCE_SLOT, UPDATE_COUNT_SLOT, QUOTE_SLOT].iter() {
+                oracle.pack_buffer_slot_into_slice(index, &mut data);
+            }
+            return Ok(());
+        }
+        OracleVersion::pack(OracleVersion::OracleV1(oracle), &mut data)?;
+        Ok(())
+    }
+
+    /// Processes an [UpdateBatchSameAccount].
+    pub fn process_update_batch_same_account(
+        program_id: &Pubkey,
+        samples: &[(i64, u64, u64)],
+        accounts: &[AccountInfo],
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let data_account_info = next_account(account_info_iter, "oracle")?;
+        let authority_info = next_account(account_info_iter, "authority")?;
+
+        require_owner(data_account_info, program_id)?;
+        require_writable(data_account_info)?;
+        Self::check_authority(data_account_info, authority_info)?;
+
+        let mut data = data_account_info.data.borrow_mut();
+        let mut oracle = unpack_base(&data)?;
+        require_not_frozen(&oracle)?;
+        for &(price, confidence, slot) in samples {
+            oracle.push_history(price, confidence, slot);
+        }
+        OracleVersion::pack(OracleVersion::OracleV1(oracle), &mut data)?;
+        Ok(())
+    }
+
+    /// Processes a [PrepareTransfer](OracleInstruction::PrepareTransfer).
+    pub fn process_prepare_transfer(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let data_account_info = next_account(account_info_iter, "oracle")?;
+        let authority_info = next_account(account_info_iter, "authority")?;
 
-        let src = OracleVersion::OracleV1(OracleV1 {
+        require_owner(data_account_info, program_id)?;
+        require_writable(data_account_info)?;
+        Self::check_authority(data_account_info, authority_info)?;
+
+        let mut data = data_account_info.data.borrow_mut();
+        let mut oracle = unpack_base(&data)?;
+        let mut flags = oracle.flags();
+        flags.insert(OracleFlags::FROZEN);
+        oracle.drv4 = flags.bits() as i32;
+        OracleVersion::pack(OracleVersion::OracleV1(oracle), &mut data)?;
+        Ok(())
+    }
+
+    /// Processes a [ClearData](OracleInstruction::ClearData).
+    pub fn process_clear_data(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let data_account_info = next_account(account_info_iter, "oracle")?;
+        let authority_info = next_account(account_info_iter, "authority")?;
+
+        require_owner(data_account_info, program_id)?;
+        require_writable(data_account_info)?;
+        Self::check_authority(data_account_info, authority_info)?;
+        require_not_frozen(&unpack_base(&data_account_info.data.borrow())?)?;
+
+        data_account_info.data.borrow_mut().fill(0);
+        Ok(())
+    }
+
+    /// Processes a [PublishComponent].
+    pub fn process_publish_component(
+        program_id: &Pubkey,
+        index: u8,
+        price: i64,
+        confidence: u64,
+        accounts: &[AccountInfo],
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let data_account_info = next_account(account_info_iter, "oracle")?;
+        let authority_info = next_account(account_info_iter, "authority")?;
+        let publisher_info = next_account(account_info_iter, "publisher")?;
+
+        require_owner(data_account_info, program_id)?;
+        require_writable(data_account_info)?;
+        Self::check_authority(data_account_info, authority_info)?;
+        require_signer(publisher_info)?;
+        let mut data = data_account_info.data.borrow_mut();
+        let mut oracle = unpack_base(&data)?;
+        check_component_index(&oracle, index)?;
+        require_not_frozen(&oracle)?;
+        if oracle.component_publisher(index as usize) != *publisher_info.key {
+            return Err(OracleError::IncorrectAuthority.into());
+        }
+        oracle.set_component(index as usize, publisher_info.key, price, confidence);
+        OracleVersion::pack(OracleVersion::OracleV1(oracle), &mut data)?;
+        Ok(())
+    }
+
+    /// Processes an [AddPublisher].
+    pub fn process_add_publisher(
+        program_id: &Pubkey,
+        index: u8,
+        publisher: &Pubkey,
+        accounts: &[AccountInfo],
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let data_account_info = next_account(account_info_iter, "oracle")?;
+        let authority_info = next_account(account_info_iter, "authority")?;
+
+        require_owner(data_account_info, program_id)?;
+        require_writable(data_account_info)?;
+        Self::check_authority(data_account_info, authority_info)?;
+        let mut data = data_account_info.data.borrow_mut();
+        let mut oracle = unpack_base(&data)?;
+        require_not_frozen(&oracle)?;
+        check_component_index(&oracle, index)?;
+        if *publisher == Pubkey::default()
+            || oracle.component_publisher(index as usize) != Pubkey::default()
+            || oracle.component_publishers().iter().any(|(key, _)| key == publisher)
+        {
+            return Err(ProgramError::InvalidArgument);
+        }
+        oracle.set_component_publisher(index as usize, publisher);
+        oracle.num_quoters = oracle.component_publishers().len() as u32;
+        OracleVersion::pack(OracleVersion::OracleV1(oracle), &mut data)?;
+        Ok(())
+    }
+
+    /// Processes a [SetMetadata](OracleInstruction::SetMetadata).
+    pub fn process_set_metadata(
+        program_id: &Pubkey,
+        metadata: &[u8; 32],
+        accounts: &[AccountInfo],
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let data_account_info = next_account(account_info_iter, "oracle")?;
+        let authority_info = next_account(account_info_iter, "authority")?;
+
+        require_owner(data_account_info, program_id)?;
+        require_writable(data_account_info)?;
+        Self::check_authority(data_account_info, authority_info)?;
+        let mut data = data_account_info.data.borrow_mut();
+        let mut oracle = unpack_base(&data)?;
+        require_not_frozen(&oracle)?;
+        oracle.set_metadata(metadata);
+        OracleVersion::pack(OracleVersion::OracleV1(oracle), &mut data)?;
+        Ok(())
+    }
+
+    /// Processes a [ProposeAuthority](OracleInstruction::ProposeAuthority).
+    pub fn process_propose_authority(
+        program_id: &Pubkey,
+        new_authority: &Pubkey,
+        accounts: &[AccountInfo],
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let data_account_info = next_account(account_info_iter, "oracle")?;
+        let authority_info = next_account(account_info_iter, "authority")?;
+
+        require_owner(data_account_info, program_id)?;
+        require_writable(data_account_info)?;
+        Self::check_authority(data_account_info, authority_info)?;
+        let mut data = data_account_info.data.borrow_mut();
+        let mut oracle = unpack_base(&data)?;
+        require_not_frozen(&oracle)?;
+        oracle.set_pending_authority(new_authority);
+        OracleVersion::pack(OracleVersion::OracleV1(oracle), &mut data)?;
+        Ok(())
+    }
+
+    /// Processes an [AcceptAuthority](OracleInstruction::AcceptAuthority).
+    pub fn process_accept_authority(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let data_account_info = next_account(account_info_iter, "oracle")?;
+        let pending_authority_info = next_account(account_info_iter, "pending authority")?;
+
+        require_owner(data_account_info, program_id)?;
+        require_writable(data_account_info)?;
+        require_signer(pending_authority_info)?;
+        let mut data = data_account_info.data.borrow_mut();
+        let mut oracle = unpack_base(&data)?;
+        require_not_frozen(&oracle)?;
+        if oracle.magic != MAGIC {
+            return Err(ProgramError::UninitializedAccount);
+        }
+        let pending_authority = oracle.pending_authority();
+        if pending_authority == Pubkey::default() || pending_authority != *pending_authority_info.key {
+            return Err(OracleError::IncorrectAuthority.into());
+        }
+        oracle.set_authority(&pending_authority);
+        oracle.set_pending_authority(&Pubkey::default());
+        OracleVersion::pack(OracleVersion::OracleV1(oracle), &mut data)?;
+        Ok(())
+    }
+
+    /// Processes a [SetPriceType](OracleInstruction::SetPriceType).
+    pub fn process_set_price_type(
+        program_id: &Pubkey,
+        price_type: u32,
+        accounts: &[AccountInfo],
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let data_account_info = next_account(account_info_iter, "oracle")?;
+        let authority_info = next_account(account_info_iter, "authority")?;
+
+        require_owner(data_account_info, program_id)?;
+        require_writable(data_account_info)?;
+        Self::check_authority(data_account_info, authority_info)?;
+        let price_type = PriceType::try_from(price_type)?;
+        let mut data = data_account_info.data.borrow_mut();
+        let mut oracle = unpack_base(&data)?;
+        require_not_frozen(&oracle)?;
+        oracle.price_type = price_type.into();
+        OracleVersion::pack(OracleVersion::OracleV1(oracle), &mut data)?;
+        Ok(())
+    }
+
+    /// Processes a [RemovePublisher](OracleInstruction::RemovePublisher).
+    pub fn process_remove_publisher(
+        program_id: &Pubkey,
+        index: u8,
+        accounts: &[AccountInfo],
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let data_account_info = next_account(account_info_iter, "oracle")?;
+        let authority_info = next_account(account_info_iter, "authority")?;
+
+        require_owner(data_account_info, program_id)?;
+        require_writable(data_account_info)?;
+        Self::check_authority(data_account_info, authority_info)?;
+        let mut data = data_account_info.data.borrow_mut();
+        let mut oracle = unpack_base(&data)?;
+        check_component_index(&oracle, index)?;
+        if oracle.component_publisher(index as usize) == Pubkey::default() {
+            return Err(ProgramError::InvalidArgument);
+        }
+        oracle.set_component(index as usize, &Pubkey::default(), 0, 0);
+        // never drops below one quoter, even with the allowlist emptied
+        oracle.num_quoters = (oracle.component_publishers().len() as u32).max(1);
+        OracleVersion::pack(OracleVersion::OracleV1(oracle), &mut data)?;
+        Ok(())
+    }
+
+    /// Checks that updates aren't paused. Every update must pass the program
+    /// config PDA as its trailing account; a PDA that was never created reads
+    /// as unpaused.
+    fn check_not_paused(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+        let config_address = find_config_address(program_id).0;
+        let config_info = match accounts.last() {
+            Some(config_info) if *config_info.key == config_address => config_info,
+            _ => {
+                msg!("Missing program config account {}", config_address);
+                return Err(ProgramError::NotEnoughAccountKeys);
+            }
+        };
+        if config_info.data_is_empty() {
+            return Ok(());
+        }
+        require_owner(config_info, program_id)?;
+        if ProgramConfig::unpack(&config_info.data.borrow())?.paused {
+            return Err(OracleError::ProgramPaused.into());
+        }
+        Ok(())
+    }
+
+    /// Processes a [OracleInstruction::Pause] or [OracleInstruction::Unpause].
+    pub fn process_set_paused(
+        program_id: &Pubkey,
+        paused: bool,
+        accounts: &[AccountInfo],
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let config_info = next_account(account_info_iter, "config")?;
+        let admin_info = next_account(account_info_iter, "admin")?;
+        let system_program_info = next_account(account_info_iter, "system program")?;
+
+        require_signer(admin_info)?;
+        if *admin_info.key != crate::admin::id() {
+            return Err(OracleError::IncorrectAuthority.into());
+        }
+        let (config_key, bump) = find_config_address(program_id);
+        if *config_info.key != config_key {
+            return Err(ProgramError::InvalidArgument);
+        }
+
+        if config_info.data_is_empty() {
+            let rent = Rent::get()?;
+            invoke_signed(
+                &system_instruction::create_account(
+                    admin_info.key,
+                    config_info.key,
+                    rent.minimum_balance(ProgramConfig::LEN),
+                    ProgramConfig::LEN as u64,
+                    program_id,
+                ),
+                &[
+                    admin_info.clone(),
+                    config_info.clone(),
+                    system_program_info.clone(),
+                ],
+                &[&[CONFIG_SEED, &[bump]]],
+            )?;
+        }
+        require_owner(config_info, program_id)?;
+
+        ProgramConfig {
+            is_initialized: true,
+            paused,
+        }
+        .pack_into_slice(&mut config_info.data.borrow_mut());
+        Ok(())
+    }
+
+    /// Processes an [Initialize].
+    pub fn process_initialize(
+        program_id: &Pubkey,
+        initialize: Initialize,
+        accounts: &[AccountInfo],
+    ) -> ProgramResult {
+        let Initialize {
+            min_confidence,
+            reject_below_min_confidence,
+            flags,
+            base_decimals,
+            quote_decimals,
+            aggregation_trim,
+            initial_price,
+            max_staleness_slots,
+            valid_slot_delay,
+            num_quoters,
+        } = initialize;
+        let account_info_iter = &mut accounts.iter();
+        let data_account_info = next_account(account_info_iter, "oracle")?;
+        let authority_info = next_account(account_info_iter, "authority")?;
+
+        require_owner(data_account_info, program_id)?;
+        require_signer(data_account_info)?;
+        require_writable(data_account_info)?;
+
+        let wide = data_account_info.data_len() == OracleV2::LEN;
+        let existing = {
+            let data = data_account_info.data.borrow();
+            unpack_base(&data)?
+        };
+        if existing.magic == MAGIC {
+            return Err(ProgramError::AccountAlreadyInitialized);
+        }
+        if !existing.buffer_is_clean() {
+            msg!("Buffer held stale data, overwriting it");
+        }
+        let rent = Rent::get()?;
+        if data_account_info.lamports() < rent.minimum_balance(data_account_info.data_len()) {
+            return Err(OracleError::NotRentExempt.into());
+        }
+        if 2 * aggregation_trim as usize >= MAX_COMPONENTS || num_quoters == 0 {
+            return Err(OracleError::InvalidInstruction.into());
+        }
+
+        if initial_price == i64::MIN {
+            return Err(OracleError::InvalidPrice.into());
+        }
+        let (ema_price_numerator, ema_price_denominator) = if initial_price == 0 {
+            (NUMERATOR as i128, DENOMINATOR)
+        } else {
+            ema_step(0, 0, initial_price as i128, EMA_WEIGHT, i64::MAX as i128)?
+        };
+
+        let mut buffer = [0u128; 192];
+        buffer[MIN_CONFIDENCE_SLOT] = min_confidence as u128;
+        buffer[REJECT_BELOW_MIN_CONFIDENCE_SLOT] = reject_below_min_confidence as u128;
+        buffer[AGGREGATION_TRIM_SLOT] = aggregation_trim as u128;
+        buffer[MAX_STALENESS_SLOT] = max_staleness_slots as u128;
+        buffer[VALID_SLOT_DELAY_SLOT] = valid_slot_delay as u128;
+
+        let mut oracle = OracleV1 {
             magic: MAGIC,
             version: VERSION,
             acctype: ATYPE,
@@ -77,38 +1079,93 @@ impl Processor {
             price_type: TYPE,
             exponent: EXPONENT,
             num_component_prices: NUM_COMPONENT,
-            num_quoters: NUM_QUOTERS,
-            last_slot: solana_slot,
-            valid_slot: solana_slot,
-            ema_price_value: price as u64,
-            ema_price_numerator: NUMERATOR,
-            ema_price_denominator: DENOMINATOR,
-            ema_confidence_value: confidence,
+            // resynced to the allowlist size as publishers are added
+            num_quoters,
+            last_slot: 0,
+            valid_slot: 0,
+            ema_price_value: initial_price as u64,
+            ema_price_numerator: ema_price_numerator as i64 as u64,
+            ema_price_denominator,
+            ema_confidence_value: 0,
             ema_confidence_numerator: NUMERATOR,
             ema_confidence_denominator: DENOMINATOR,
-            timestamp: solana_timestamp,
+            timestamp: 0,
             min_publishers: MIN_PUBLISHERS,
-            drv2: 0,
-            drv3: 0,
-            drv4: 0,
-            product_account_key : *data_account_info.key,
-            next_price_account_key : *data_account_info.key,
-            previous_slot: solana_slot,
-            previous_price_component: price,
-            previous_confidence_component: confidence,
-            previous_timestamp: solana_timestamp,
-            price_component: price,
-            confidence_component: confidence,
-            status: status,
+            drv2: quote_decimals as i8,
+            drv3: base_decimals as i16,
+            drv4: flags.bits() as i32,
+            product_account_key: *data_account_info.key,
+            next_price_account_key: Pubkey::default(),
+            previous_slot: 0,
+            previous_price_component: 0,
+            previous_confidence_component: 0,
+            previous_timestamp: 0,
+            price_component: 0,
+            confidence_component: 0,
+            status: 0,
             corporate_action: ACTION,
-            publish_slot: solana_slot,
-            buffer: [0;192],
-        });
+            publish_slot: 0,
+            buffer,
+        };
+        oracle.set_authority(authority_info.key);
 
-        OracleVersion::pack(src, &mut data_account_info.data.borrow_mut())?;
+        let oracle = if wide {
+            oracle.version = VERSION_V2;
+            oracle.size = SIZE_V2;
+            OracleVersion::OracleV2(OracleV2 {
+                base: oracle,
+                price: 0,
+            })
+        } else {
+            OracleVersion::OracleV1(oracle)
+        };
+        OracleVersion::pack(oracle, &mut data_account_info.data.borrow_mut())?;
         Ok(())
     }
 
+    /// Processes a [SetValidSlot].
+    #[cfg(feature = "test-helpers")]
+    pub fn process_set_valid_slot(
+        program_id: &Pubkey,
+        valid_slot: u64,
+        last_slot: u64,
+        accounts: &[AccountInfo],
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let data_account_info = next_account(account_info_iter, "oracle")?;
+        let authority_info = next_account(account_info_iter, "authority")?;
+
+        require_owner(data_account_info, program_id)?;
+        require_writable(data_account_info)?;
+        Self::check_authority(data_account_info, authority_info)?;
+
+        let mut data = data_account_info.data.borrow_mut();
+        let mut oracle = unpack_base(&data)?;
+        oracle.valid_slot = valid_slot;
+        oracle.last_slot = last_slot;
+        OracleVersion::pack(OracleVersion::OracleV1(oracle), &mut data)?;
+        Ok(())
+    }
+
+    /// Processes an [UpdateWithSlot].
+    #[cfg(feature = "test-helpers")]
+    pub fn process_update_with_slot(
+        program_id: &Pubkey,
+        update: &Update,
+        slot: u64,
+        accounts: &[AccountInfo],
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let data_account_info = next_account(account_info_iter, "oracle")?;
+
+        require_owner(data_account_info, program_id)?;
+        require_signer(data_account_info)?;
+        require_writable(data_account_info)?;
+
+        let timestamp = unpack_base(&data_account_info.data.borrow())?.timestamp;
+        Self::write_update_at(data_account_info, update, None, slot, timestamp)
+    }
+
     /// Processes an [Instruction].
     pub fn process(program_id: &Pubkey, accounts: &[AccountInfo], input: &[u8]) -> ProgramResult {
         Self::process_with_constraints(program_id, accounts, input)
@@ -120,7 +1177,22 @@ impl Processor {
         accounts: &[AccountInfo],
         input: &[u8],
     ) -> ProgramResult {
+        validate_instruction(input)?;
         let instruction = OracleInstruction::unpack(input)?;
+        if matches!(
+            instruction,
+            OracleInstruction::Update(_)
+                | OracleInstruction::UpdateWithAuthority(_)
+                | OracleInstruction::UpdateBatchSameAccount(_)
+                | OracleInstruction::PublishComponent(_)
+                | OracleInstruction::UpdateSigned(_)
+                | OracleInstruction::UpdatePctConfidence(_)
+                | OracleInstruction::UpdateWide(_)
+                | OracleInstruction::UpdateQuote(_)
+                | OracleInstruction::UpdateConfidence(_)
+        ) {
+            Self::check_not_paused(program_id, accounts)?;
+        }
         match instruction {
             OracleInstruction::Update(Update {
                 price,
@@ -135,6 +1207,100 @@ impl Processor {
                     accounts,
                 )
             }
+            #[cfg(feature = "test-helpers")]
+            OracleInstruction::SetValidSlot(SetValidSlot {
+                valid_slot,
+                last_slot,
+            }) => {
+                Self::process_set_valid_slot(
+                    program_id,
+                    valid_slot,
+                    last_slot,
+                    accounts,
+                )
+            }
+            #[cfg(feature = "test-helpers")]
+            OracleInstruction::UpdateWithSlot(UpdateWithSlot { update, slot }) => {
+                Self::process_update_with_slot(program_id, &update, slot, accounts)
+            }
+            OracleInstruction::UpdateWithAuthority(Update {
+                price,
+                confidence,
+                status,
+            }) => {
+                Self::process_update_with_authority(
+                    program_id,
+                    price,
+                    confidence,
+                    status,
+                    accounts,
+                )
+            }
+            OracleInstruction::UpdateBatchSameAccount(UpdateBatchSameAccount { samples }) => {
+                Self::process_update_batch_same_account(program_id, &samples, accounts)
+            }
+            OracleInstruction::PrepareTransfer => {
+                Self::process_prepare_transfer(program_id, accounts)
+            }
+            OracleInstruction::AddPublisher(AddPublisher { index, publisher }) => {
+                Self::process_add_publisher(program_id, index, &publisher, accounts)
+            }
+            OracleInstruction::RemovePublisher { index } => {
+                Self::process_remove_publisher(program_id, index, accounts)
+            }
+            OracleInstruction::SetMetadata { metadata } => {
+                Self::process_set_metadata(program_id, &metadata, accounts)
+            }
+            OracleInstruction::ProposeAuthority { new_authority } => {
+                Self::process_propose_authority(program_id, &new_authority, accounts)
+            }
+            OracleInstruction::AcceptAuthority => {
+                Self::process_accept_authority(program_id, accounts)
+            }
+            OracleInstruction::SetPriceType { price_type } => {
+                Self::process_set_price_type(program_id, price_type, accounts)
+            }
+            OracleInstruction::UpdateQuote(UpdateQuote { bid, ask, status }) => {
+                Self::process_update_quote(program_id, bid, ask, status, accounts)
+            }
+            OracleInstruction::UpdateConfidence(UpdateConfidence { confidence, status }) => {
+                Self::process_update_confidence(program_id, confidence, status, accounts)
+            }
+            OracleInstruction::ClearData => Self::process_clear_data(program_id, accounts),
+            OracleInstruction::UpdatePctConfidence(UpdatePctConfidence {
+                price,
+                confidence_bps,
+                status,
+            }) => {
+                let confidence = confidence_from_bps(price, confidence_bps)?;
+                Self::process_update(program_id, price, confidence, status, accounts)
+            }
+            OracleInstruction::UpdateWide(UpdateWide {
+                price,
+                confidence,
+                status,
+            }) => Self::process_update_wide(program_id, price, confidence, status, accounts),
+            OracleInstruction::Pause => Self::process_set_paused(program_id, true, accounts),
+            OracleInstruction::Unpause => Self::process_set_paused(program_id, false, accounts),
+            OracleInstruction::UpdateSigned(update_signed) => {
+                Self::process_update_signed(program_id, update_signed, accounts)
+            }
+            OracleInstruction::PublishComponent(PublishComponent {
+                index,
+                price,
+                confidence,
+            }) => {
+                Self::process_publish_component(
+                    program_id,
+                    index,
+                    price,
+                    confidence,
+                    accounts,
+                )
+            }
+            OracleInstruction::Initialize(initialize) => {
+                Self::process_initialize(program_id, initialize, accounts)
+            }
         }
     }
 }
@@ -149,6 +1315,24 @@ impl PrintProgramError for OracleError {
             OracleError::IncorrectSigner => {
                 msg!("Error: Address of the provided signer account is incorrect")
             }
+            OracleError::InvalidStatus => msg!("Error: Invalid price status"),
+            OracleError::AccountNotWritable => msg!("Error: Oracle account is not writable"),
+            OracleError::ConfidenceBelowMinimum => {
+                msg!("Error: Confidence is below the configured minimum")
+            }
+            OracleError::IncorrectAuthority => {
+                msg!("Error: Address of the provided authority account is incorrect")
+            }
+            OracleError::ArithmeticError => msg!("Error: Arithmetic overflow or division by zero"),
+            OracleError::InvalidPrice => msg!("Error: Invalid price"),
+            OracleError::AccountFrozen => msg!("Error: Oracle account is frozen"),
+            OracleError::InvalidSignature => msg!("Error: Invalid update signature"),
+            OracleError::ProgramPaused => msg!("Error: Program is paused"),
+            OracleError::TimestampOutOfRange => msg!("Error: Timestamp out of range"),
+            OracleError::ComponentIndexOutOfRange => msg!("Error: Component index out of range"),
+            OracleError::NotRentExempt => msg!("Error: Account is not rent exempt"),
+            OracleError::InvalidPriceType => msg!("Error: Invalid price type"),
+            OracleError::UntrustedPrice => msg!("Error: Untrusted price"),
         }
     }
 }
@@ -1,13 +1,396 @@
 //! State transition types
+use crate::{
+    error::OracleError,
+    math::apply_exponent,
+    consts::{MAGIC, SIZE, VERSION, VERSION_V2},
+};
 use arrayref::{array_mut_ref, array_ref, array_refs, mut_array_refs};
 use enum_dispatch::enum_dispatch;
 use solana_program::{
+    hash::hashv,
     program_error::ProgramError,
     program_pack::{IsInitialized, Pack, Sealed},
     pubkey::Pubkey,
 };
+use std::{cmp::Ordering, convert::TryFrom};
+
+/// Basis points in one whole.
+pub const BPS_DENOMINATOR: u64 = 10_000;
+
+/// Size of the fixed fields preceding the buffer.
+pub const HEADER_LEN: usize = 240;
+
+/// Name and size in bytes of each field of the packed [OracleV1] layout, in
+/// order. Must be kept in sync with [OracleV1]'s `Pack` implementation.
+const SCHEMA: &str = "magic:4,version:4,acctype:4,size:4,price_type:4,exponent:4,\
+    num_component_prices:4,num_quoters:4,last_slot:8,valid_slot:8,ema_price_value:8,\
+    ema_price_numerator:8,ema_price_denominator:8,ema_confidence_value:8,\
+    ema_confidence_numerator:8,ema_confidence_denominator:8,timestamp:8,min_publishers:1,\
+    drv2:1,drv3:2,drv4:4,product_account_key:32,next_price_account_key:32,previous_slot:8,\
+    previous_price_component:8,previous_confidence_component:8,previous_timestamp:8,\
+    price_component:8,confidence_component:8,status:4,corporate_action:4,publish_slot:8,\
+    buffer:3072";
+
+/// Hash of the packed [OracleV1] layout. Clients compare it against the value
+/// they were built with to detect a deployment with an incompatible layout.
+pub const SCHEMA_HASH: [u8; 32] = layout_hash(SCHEMA.as_bytes());
+
+/// Anchor account discriminator of [OracleV1], the first 8 bytes of
+/// `sha256("account:OracleV1")`. Oracle accounts don't start with it, since
+/// that would overwrite the magic, so Anchor clients match on it explicitly.
+pub const ACCOUNT_DISCRIMINATOR: [u8; 8] = [196, 165, 211, 138, 148, 207, 196, 108];
+
+/// 32-byte hash made of four FNV-1a 64 lanes, each with a distinct offset
+/// basis. Only meant to tell layouts apart, not to resist collisions.
+const fn layout_hash(bytes: &[u8]) -> [u8; 32] {
+    let mut hash = [0u8; 32];
+    let mut lane = 0;
+    while lane < 4 {
+        let mut state: u64 = 0xcbf2_9ce4_8422_2325 ^ lane as u64;
+        let mut i = 0;
+        while i < bytes.len() {
+            state ^= bytes[i] as u64;
+            state = state.wrapping_mul(0x0100_0000_01b3);
+            i += 1;
+        }
+        let state = state.to_le_bytes();
+        let mut j = 0;
+        while j < 8 {
+            hash[lane * 8 + j] = state[j];
+            j += 1;
+        }
+        lane += 1;
+    }
+    hash
+}
+
+/// Number of component prices that can be stored, one per quoter.
+pub const MAX_COMPONENTS: usize = 32;
+/// First buffer slot of the component prices. Each component takes one slot,
+/// price in the low 64 bits and confidence in the high 64 bits.
+pub const COMPONENT_SLOT: usize = 0;
+/// First buffer slot of the component publishers. Each publisher key takes two
+/// slots, and component `i`'s publisher starts at `PUBLISHER_SLOT + 2 * i`.
+pub const PUBLISHER_SLOT: usize = 32;
+
+/// Number of samples kept in the price history ring buffer.
+pub const HISTORY_LEN: usize = 32;
+/// First buffer slot of the price history ring buffer. Each sample takes two
+/// slots: price and confidence in the first, slot in the second.
+pub const HISTORY_SLOT: usize = 96;
+/// Buffer slot holding the total number of samples ever written to the price
+/// history; the next sample goes at this count modulo [HISTORY_LEN].
+pub const HISTORY_CURSOR_SLOT: usize = 187;
+
+/// Buffer slot holding the confidence floor applied to updates. Configuration
+/// is stored in buffer slots allocated downwards from the end of the buffer,
+/// above the price history.
+pub const MIN_CONFIDENCE_SLOT: usize = 191;
+/// Buffer slot holding whether updates below the confidence floor are rejected.
+pub const REJECT_BELOW_MIN_CONFIDENCE_SLOT: usize = 190;
+/// First of the two buffer slots holding the oracle's update authority.
+pub const AUTHORITY_SLOT: usize = 188;
+/// Buffer slot holding how many of the highest and lowest component prices
+/// [OracleState::trimmed_component_price] discards before averaging the rest.
+/// Read-side only: the program never aggregates components itself.
+pub const AGGREGATION_TRIM_SLOT: usize = 186;
+/// Buffer slot holding the age in slots past which consumers should reject the
+/// price.
+pub const MAX_STALENESS_SLOT: usize = 185;
+/// Buffer slot holding how many slots after publication a price becomes valid.
+pub const VALID_SLOT_DELAY_SLOT: usize = 184;
+/// Buffer slot holding the number of updates written since initialize.
+pub const SEQUENCE_SLOT: usize = 183;
+/// First of the two buffer slots holding the operator-defined metadata blob.
+pub const METADATA_SLOT: usize = 181;
+/// Buffer slot holding the bid and ask of the last `UpdateQuote`, bid in the
+/// low 64 bits and ask in the high 64 bits.
+pub const QUOTE_SLOT: usize = 178;
+/// Buffer slot holding the lifetime number of successful updates.
+pub const UPDATE_COUNT_SLOT: usize = 177;
+/// First of the two buffer slots holding the authority proposed to take over
+/// the oracle, all zeros when no handoff is pending.
+pub const PENDING_AUTHORITY_SLOT: usize = 179;
+
+/// Seed of the program-wide config PDA.
+pub const CONFIG_SEED: &[u8] = b"config";
+
+/// Address and bump seed of the program-wide config PDA.
+pub fn find_config_address(program_id: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[CONFIG_SEED], program_id)
+}
+
+/// Reassemble a pubkey stored across two consecutive buffer slots.
+fn pubkey_from_slots(lo: u128, hi: u128) -> Pubkey {
+    let mut key = [0u8; 32];
+    key[..16].copy_from_slice(&lo.to_le_bytes());
+    key[16..].copy_from_slice(&hi.to_le_bytes());
+    Pubkey::new_from_array(key)
+}
+
+/// Split a pubkey across two consecutive buffer slots.
+fn pubkey_to_slots(key: &Pubkey) -> (u128, u128) {
+    let key = key.to_bytes();
+    let (lo, hi) = array_refs![&key, 16, 16];
+    (u128::from_le_bytes(*lo), u128::from_le_bytes(*hi))
+}
+
+/// 10 raised to `exponent` as a float, overflowing to infinity and
+/// underflowing to zero past the range f64 can represent.
+fn pow10(exponent: i32) -> f64 {
+    10f64.powi(exponent)
+}
+
+/// Largest exponent magnitude [format_scaled] writes out in full. Past it the
+/// value is written in scientific notation rather than padded with zeros.
+const MAX_FORMATTED_EXPONENT: u32 = 38;
+
+/// `digits` scaled by 10^`exponent` as a decimal string, with as many
+/// fractional digits as the exponent calls for, or as `digits` followed by
+/// `e` and the exponent once it's too large to write out.
+fn format_scaled(negative: bool, digits: u64, exponent: i32) -> String {
+    let sign = if negative { "-" } else { "" };
+    let digits = digits.to_string();
+    if exponent.unsigned_abs() > MAX_FORMATTED_EXPONENT {
+        return format!("{}{}e{}", sign, digits, exponent);
+    }
+    if exponent >= 0 {
+        let zeros = "0".repeat(exponent as usize);
+        return format!("{}{}{}", sign, digits, zeros);
+    }
+    let decimals = exponent.unsigned_abs() as usize;
+    let digits = format!("{:0>width$}", digits, width = decimals + 1);
+    let (whole, fraction) = digits.split_at(digits.len() - decimals);
+    format!("{}{}.{}", sign, whole, fraction)
+}
+
+/// Status of the published price.
+#[repr(u32)]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum PriceStatus {
+    /// price is not known and should not be used.
+    Unknown = 0,
+    /// price is valid and actively trading.
+    Trading = 1,
+    /// trading is halted.
+    Halted = 2,
+    /// price is being set by an auction.
+    Auction = 3,
+}
+
+impl TryFrom<u32> for PriceStatus {
+    type Error = OracleError;
+
+    fn try_from(status: u32) -> Result<Self, Self::Error> {
+        match status {
+            0 => Ok(Self::Unknown),
+            1 => Ok(Self::Trading),
+            2 => Ok(Self::Halted),
+            3 => Ok(Self::Auction),
+            _ => Err(OracleError::InvalidStatus),
+        }
+    }
+}
+
+impl From<PriceStatus> for u32 {
+    fn from(status: PriceStatus) -> Self {
+        status as u32
+    }
+}
+
+/// Kind of value a feed publishes, stored in the `price_type` field.
+#[repr(u32)]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum PriceType {
+    /// price of an asset.
+    Price = 1,
+    /// interest rate.
+    Rate = 2,
+    /// volatility.
+    Volatility = 3,
+}
+
+impl TryFrom<u32> for PriceType {
+    type Error = OracleError;
+
+    fn try_from(price_type: u32) -> Result<Self, Self::Error> {
+        match price_type {
+            1 => Ok(Self::Price),
+            2 => Ok(Self::Rate),
+            3 => Ok(Self::Volatility),
+            _ => Err(OracleError::InvalidPriceType),
+        }
+    }
+}
+
+impl From<PriceType> for u32 {
+    fn from(price_type: PriceType) -> Self {
+        price_type as u32
+    }
+}
+
+/// Corporate action announced through the `corporate_action` field.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum CorporateAction {
+    /// no corporate action is pending.
+    None,
+    /// stock split.
+    Split,
+    /// dividend distribution.
+    Dividend,
+    /// merger or acquisition.
+    Merger,
+    /// delisting of the asset.
+    Delisting,
+    /// a code this version of the program doesn't know about.
+    Other(u32),
+}
+
+impl From<u32> for CorporateAction {
+    fn from(code: u32) -> Self {
+        match code {
+            0 => Self::None,
+            1 => Self::Split,
+            2 => Self::Dividend,
+            3 => Self::Merger,
+            4 => Self::Delisting,
+            code => Self::Other(code),
+        }
+    }
+}
+
+/// Feature toggles stored in the repurposed `drv4` field, set on initialize.
+///
+/// * bit 0, `EMA_ENABLED`: updates fold into the ema price and confidence;
+///   without it the ema fields simply mirror the latest update.
+/// * bit 1, `REJECT_ZERO_PRICE`: a price of zero is treated as a "no data"
+///   sentinel and rejected.
+/// * bit 2, `SKIP_BUFFER`: updates leave the buffer untouched apart from the
+///   sequence, update count and quote, saving the compute of rewriting it, at
+///   the cost of not recording price history.
+/// * bit 3, `FROZEN`: set by `PrepareTransfer` while the account migrates to a
+///   new program; no further updates are accepted.
+/// * bit 4, `HAS_PRICE`: set by the first update, telling a real price apart
+///   from the one seeded on initialize.
+///
+/// All other bits are reserved and must be zero.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct OracleFlags(u32);
+
+impl OracleFlags {
+    /// Updates fold into the ema price and confidence.
+    pub const EMA_ENABLED: Self = Self(1 << 0);
+    /// Updates with a zero price are rejected.
+    pub const REJECT_ZERO_PRICE: Self = Self(1 << 1);
+    /// Updates write only the fields preceding the buffer and the counters.
+    pub const SKIP_BUFFER: Self = Self(1 << 2);
+    /// The account is frozen for migration and rejects updates.
+    pub const FROZEN: Self = Self(1 << 3);
+    /// The oracle has been updated at least once since initialize.
+    pub const HAS_PRICE: Self = Self(1 << 4);
+
+    const ALL: u32 = Self::EMA_ENABLED.0
+        | Self::REJECT_ZERO_PRICE.0
+        | Self::SKIP_BUFFER.0
+        | Self::FROZEN.0
+        | Self::HAS_PRICE.0;
+
+    /// No flags set.
+    pub const fn empty() -> Self {
+        Self(0)
+    }
+
+    /// Raw bits of the flags.
+    pub const fn bits(&self) -> u32 {
+        self.0
+    }
+
+    /// Flags from raw bits, or `None` if any reserved bit is set.
+    pub const fn from_bits(bits: u32) -> Option<Self> {
+        if bits & !Self::ALL == 0 {
+            Some(Self(bits))
+        } else {
+            None
+        }
+    }
+
+    /// Flags from raw bits, dropping any reserved bits.
+    pub const fn from_bits_truncate(bits: u32) -> Self {
+        Self(bits & Self::ALL)
+    }
+
+    /// Whether every flag in `other` is set.
+    pub const fn contains(&self, other: Self) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    /// Set every flag in `other`.
+    pub fn insert(&mut self, other: Self) {
+        self.0 |= other.0;
+    }
+
+    /// Clear every flag in `other`.
+    pub fn remove(&mut self, other: Self) {
+        self.0 &= !other.0;
+    }
+}
+
+impl std::ops::BitOr for OracleFlags {
+    type Output = Self;
+
+    fn bitor(self, other: Self) -> Self {
+        Self(self.0 | other.0)
+    }
+}
+
+/// Spot and ema prices with their confidences, read together.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct OraclePrices {
+    /// the current price.
+    pub spot: i64,
+    /// confidence interval around the current price.
+    pub spot_conf: u64,
+    /// exponentially moving average price.
+    pub ema: i64,
+    /// exponentially moving average confidence interval.
+    pub ema_conf: u64,
+    /// price exponent shared by all of the above.
+    pub exponent: i32,
+}
+
+/// The `previous_*` fields describing the prior update, read together.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct PreviousUpdate {
+    /// valid slot of the previous update.
+    pub slot: u64,
+    /// aggregate price of the previous update.
+    pub price: i64,
+    /// confidence interval of the previous update.
+    pub confidence: u64,
+    /// unix timestamp of the previous update.
+    pub timestamp: i64,
+}
+
+/// Age, status and validity of a feed at a point in time, read together.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct OracleHealth {
+    /// slots since the price was published.
+    pub age_slots: u64,
+    /// seconds since the price's timestamp.
+    pub age_seconds: u64,
+    /// status of the price, `Unknown` if the stored status isn't recognized.
+    pub status: PriceStatus,
+    /// whether the price is within the feed's advertised max staleness.
+    pub is_fresh: bool,
+    /// whether the status is `Trading`.
+    pub is_trading: bool,
+}
 
 /// Trait representing access to program state across all versions
+///
+/// The provided read-side helpers never panic on the values an account may
+/// hold: they saturate, or return `None`/`false` where a saturated value would
+/// be misleading.
 #[enum_dispatch]
 pub trait OracleState {
     /// magic number.
@@ -46,11 +429,11 @@ pub trait OracleState {
     fn timestamp(&self) -> i64;
     /// min publishers for valid price.
     fn min_publishers(&self) -> u8;
-    /// space for future derived values.
+    /// decimals of the quote token, see [OracleState::quote_decimals].
     fn drv2(&self) -> i8;
-    /// space for future derived values.
+    /// decimals of the base token, see [OracleState::base_decimals].
     fn drv3(&self) -> i16;
-    /// space for future derived values.
+    /// feature flags, see [OracleFlags].
     fn drv4(&self) -> i32;
     /// product account key.
     fn product_account_key(&self) -> &Pubkey;
@@ -76,199 +459,1406 @@ pub trait OracleState {
     fn publish_slot(&self) -> u64;
     /// price components one per quoter.
     fn buffer(&self) -> [u128;192];
+    /// single entry of the buffer.
+    fn buffer_slot(&self, index: usize) -> u128;
+
+    /// Whether every buffer entry is zero, as expected of an account that has
+    /// never been initialized.
+    fn buffer_is_clean(&self) -> bool {
+        (0..192).all(|index| self.buffer_slot(index) == 0)
+    }
+
+    /// Aggregate price at full width. Only differs from `price_component` for
+    /// [OracleV2] prices beyond the range of an `i64`.
+    fn price_i128(&self) -> i128 {
+        self.price_component() as i128
+    }
+
+    /// Whether this account terminates its linked list, marked by an all-zero
+    /// next price account key.
+    fn is_list_tail(&self) -> bool {
+        *self.next_price_account_key() == Pubkey::default()
+    }
+
+    /// Whether `next`, stored at `next_key`, is a consistent successor of this
+    /// oracle: this oracle links to `next_key` and both belong to the same
+    /// product. Packed oracles don't record their own address, so the caller
+    /// supplies the key the successor was read from.
+    fn validates_next(&self, next_key: &Pubkey, next: &dyn OracleState) -> bool {
+        !self.is_list_tail()
+            && self.next_price_account_key() == next_key
+            && self.product_account_key() == next.product_account_key()
+    }
+
+    /// Whether `other` publishes the same feed: the same product and price
+    /// type. Oracles carry no symbol, so the product key stands in for it.
+    fn same_feed(&self, other: &dyn OracleState) -> bool {
+        self.product_account_key() == other.product_account_key()
+            && self.price_type() == other.price_type()
+    }
+
+    /// Decimals of the quote token the price is denominated in, stored in the
+    /// `drv2` field.
+    fn quote_decimals(&self) -> u8 {
+        self.drv2() as u8
+    }
+
+    /// Decimals of the base token being priced, stored in the `drv3` field.
+    fn base_decimals(&self) -> u8 {
+        self.drv3() as u8
+    }
+
+    /// Quote token amount, in the quote token's smallest unit, that
+    /// `base_amount` of the base token's smallest unit is worth. Negative prices
+    /// yield zero and overflow saturates at `u128::MAX`.
+    fn scaled_price_for_amount(&self, base_amount: u64) -> u128 {
+        let price = match u64::try_from(self.price_component()) {
+            Ok(price) => price,
+            Err(_) => return 0,
+        };
+        // base_amount < 2^64 and price < 2^63, so the product fits
+        let value = base_amount as i128 * price as i128;
+        let exponent =
+            self.exponent() as i64 + self.quote_decimals() as i64 - self.base_decimals() as i64;
+        match i32::try_from(exponent).ok().and_then(|exponent| apply_exponent(value, exponent)) {
+            Some(scaled) => scaled as u128,
+            None if exponent < 0 || value == 0 => 0,
+            None => u128::MAX,
+        }
+    }
+
+    /// Kind of value the feed publishes, failing with `InvalidPriceType` if the
+    /// stored price type isn't recognized.
+    fn price_type_enum(&self) -> Result<PriceType, OracleError> {
+        PriceType::try_from(self.price_type())
+    }
+
+    /// Feature toggles stored in the `drv4` field.
+    fn flags(&self) -> OracleFlags {
+        OracleFlags::from_bits_truncate(self.drv4() as u32)
+    }
+
+    /// Authority allowed to sign updates on the oracle's behalf.
+    fn authority(&self) -> Pubkey {
+        pubkey_from_slots(
+            self.buffer_slot(AUTHORITY_SLOT),
+            self.buffer_slot(AUTHORITY_SLOT + 1),
+        )
+    }
+
+    /// Bid of the last `UpdateQuote`, zero if the feed never published a quote.
+    fn bid(&self) -> i64 {
+        self.buffer_slot(QUOTE_SLOT) as u64 as i64
+    }
+
+    /// Ask of the last `UpdateQuote`, zero if the feed never published a quote.
+    fn ask(&self) -> i64 {
+        (self.buffer_slot(QUOTE_SLOT) >> 64) as u64 as i64
+    }
+
+    /// Authority proposed to take over the oracle, the default pubkey when no
+    /// handoff is pending.
+    fn pending_authority(&self) -> Pubkey {
+        pubkey_from_slots(
+            self.buffer_slot(PENDING_AUTHORITY_SLOT),
+            self.buffer_slot(PENDING_AUTHORITY_SLOT + 1),
+        )
+    }
+
+    /// Operator-defined metadata blob, such as the hash of a source URL.
+    fn metadata(&self) -> [u8; 32] {
+        pubkey_from_slots(
+            self.buffer_slot(METADATA_SLOT),
+            self.buffer_slot(METADATA_SLOT + 1),
+        )
+        .to_bytes()
+    }
+
+    /// Component `(price, confidence)` published at `index`.
+    fn component(&self, index: usize) -> (i64, u64) {
+        let values = self.buffer_slot(COMPONENT_SLOT + index);
+        (values as u64 as i64, (values >> 64) as u64)
+    }
+
+    /// Publisher of the component at `index`, all zeros when unset.
+    fn component_publisher(&self, index: usize) -> Pubkey {
+        pubkey_from_slots(
+            self.buffer_slot(PUBLISHER_SLOT + 2 * index),
+            self.buffer_slot(PUBLISHER_SLOT + 2 * index + 1),
+        )
+    }
+
+    /// Number of component slots in use: the first `num_component_prices`,
+    /// capped at [MAX_COMPONENTS]. The program rejects component writes past
+    /// it, and the read-side component helpers only look at these slots.
+    fn active_component_count(&self) -> u32 {
+        self.num_component_prices().min(MAX_COMPONENTS as u32)
+    }
+
+    /// Publisher and price of every component that has been published within
+    /// the [OracleState::active_component_count] slots in use.
+    fn component_publishers(&self) -> Vec<(Pubkey, i64)> {
+        (0..self.active_component_count() as usize)
+            .map(|index| (self.component_publisher(index), self.component(index).0))
+            .filter(|(publisher, _)| *publisher != Pubkey::default())
+            .collect()
+    }
+
+    /// Whether `num_quoters` matches the number of allowlisted publishers.
+    fn verify_quoter_count(&self) -> bool {
+        self.num_quoters() as usize == self.component_publishers().len()
+    }
+
+    /// Price of every published component, scaled by the exponent.
+    fn scaled_components(&self) -> Vec<f64> {
+        let scale = pow10(self.exponent());
+        self.component_publishers()
+            .into_iter()
+            .map(|(_, price)| price as f64 * scale)
+            .collect()
+    }
+
+    /// Geometric mean of the published component prices with the exponent
+    /// applied. `None` when no component has been published or any published
+    /// price is not positive.
+    fn geometric_mean_components(&self) -> Option<f64> {
+        let prices = self.scaled_components();
+        if prices.is_empty() || prices.iter().any(|&price| price <= 0.0) {
+            return None;
+        }
+        // averaging logarithms avoids overflowing the running product
+        let log_sum: f64 = prices.iter().map(|price| price.ln()).sum();
+        Some((log_sum / prices.len() as f64).exp())
+    }
+
+    /// Whether the highest and lowest published component prices are within
+    /// `max_spread_bps` of the lowest one. `false` when no component has been
+    /// published or the lowest price is not positive.
+    fn components_agree(&self, max_spread_bps: u64) -> bool {
+        let prices = self.component_publishers();
+        let (min, max) = match (
+            prices.iter().map(|(_, price)| *price).min(),
+            prices.iter().map(|(_, price)| *price).max(),
+        ) {
+            (Some(min), Some(max)) => (min as i128, max as i128),
+            _ => return false,
+        };
+        if min <= 0 {
+            return false;
+        }
+        (max - min) * BPS_DENOMINATOR as i128 <= max_spread_bps as i128 * min
+    }
+
+    /// Number of highest and lowest component prices discarded by
+    /// [OracleState::trimmed_component_price].
+    fn aggregation_trim(&self) -> usize {
+        self.buffer_slot(AGGREGATION_TRIM_SLOT) as usize
+    }
+
+    /// Mean of the published component prices after discarding the
+    /// [OracleState::aggregation_trim] highest and lowest, so a few outliers
+    /// can't drag it. `None` when trimming would leave no components.
+    /// Computed read-side: updates set `price_component` directly and never
+    /// from the components.
+    fn trimmed_component_price(&self) -> Option<i64> {
+        let mut prices: Vec<i64> = self
+            .component_publishers()
+            .into_iter()
+            .map(|(_, price)| price)
+            .collect();
+        let trim = self.aggregation_trim();
+        if trim.checked_mul(2)? >= prices.len() {
+            return None;
+        }
+        prices.sort_unstable();
+        let kept = &prices[trim..prices.len() - trim];
+        let sum: i128 = kept.iter().map(|&price| price as i128).sum();
+        i64::try_from(sum / kept.len() as i128).ok()
+    }
+
+    /// Total number of samples ever written to the price history.
+    fn history_cursor(&self) -> u64 {
+        self.buffer_slot(HISTORY_CURSOR_SLOT) as u64
+    }
+
+    /// Price history sample `(price, confidence, slot)` at ring position `index`.
+    fn history_sample(&self, index: usize) -> (i64, u64, u64) {
+        let values = self.buffer_slot(HISTORY_SLOT + 2 * index);
+        let slot = self.buffer_slot(HISTORY_SLOT + 2 * index + 1);
+        (values as u64 as i64, (values >> 64) as u64, slot as u64)
+    }
+
+    /// Stored price history samples `(price, confidence, slot)`, newest first.
+    fn recent_prices(&self) -> Vec<(i64, u64, u64)> {
+        let cursor = self.history_cursor();
+        let len = cursor.min(HISTORY_LEN as u64);
+        (1..=len)
+            .map(|back| self.history_sample(((cursor - back) % HISTORY_LEN as u64) as usize))
+            .collect()
+    }
+
+    /// Stored price history samples `(price, slot)`, oldest first, starting
+    /// wherever the ring buffer has wrapped around to.
+    fn history_iter(&self) -> Box<dyn Iterator<Item = (i64, u64)> + '_> {
+        let cursor = self.history_cursor();
+        let len = cursor.min(HISTORY_LEN as u64);
+        Box::new((cursor - len..cursor).map(move |position| {
+            let (price, _, slot) = self.history_sample((position % HISTORY_LEN as u64) as usize);
+            (price, slot)
+        }))
+    }
+
+    /// Confidence floor applied to updates.
+    fn min_confidence(&self) -> u64 {
+        self.buffer_slot(MIN_CONFIDENCE_SLOT) as u64
+    }
+
+    /// Whether updates below the confidence floor are rejected rather than
+    /// clamped up to it.
+    fn reject_below_min_confidence(&self) -> bool {
+        self.buffer_slot(REJECT_BELOW_MIN_CONFIDENCE_SLOT) != 0
+    }
+
+    /// Checks that the stored `size` field matches the length of the account
+    /// data it was read from.
+    fn verify_size(&self, data_len: usize) -> Result<(), ProgramError> {
+        if self.size() as usize != data_len {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        Ok(())
+    }
+
+    /// Whether a corporate action has been announced.
+    fn has_pending_corporate_action(&self) -> bool {
+        self.corporate_action() != 0
+    }
+
+    /// Kind of corporate action announced, [CorporateAction::None] if there
+    /// isn't one.
+    fn corporate_action_kind(&self) -> CorporateAction {
+        self.corporate_action().into()
+    }
+
+    /// Slot, price, confidence and timestamp of the previous update.
+    fn previous(&self) -> PreviousUpdate {
+        PreviousUpdate {
+            slot: self.previous_slot(),
+            price: self.previous_price_component(),
+            confidence: self.previous_confidence_component(),
+            timestamp: self.previous_timestamp(),
+        }
+    }
+
+    /// Age, status and validity at `current_slot` and `current_time`. Ages
+    /// saturate at zero when the price is ahead of the given clock, and
+    /// freshness follows [OracleState::is_expired].
+    fn health(&self, current_slot: u64, current_time: i64) -> OracleHealth {
+        let status = PriceStatus::try_from(self.status()).unwrap_or(PriceStatus::Unknown);
+        OracleHealth {
+            age_slots: current_slot.saturating_sub(self.publish_slot()),
+            age_seconds: u64::try_from(current_time.saturating_sub(self.timestamp())).unwrap_or(0),
+            status,
+            is_fresh: !self.is_expired(current_slot),
+            is_trading: status == PriceStatus::Trading,
+        }
+    }
+
+    /// Spot and ema prices with their confidences and exponent.
+    fn prices(&self) -> OraclePrices {
+        OraclePrices {
+            spot: self.price_component(),
+            spot_conf: self.confidence_component(),
+            ema: self.ema_price_value() as i64,
+            ema_conf: self.ema_confidence_value(),
+            exponent: self.exponent(),
+        }
+    }
+
+    /// Slots since the price became valid. Distinct from the age since
+    /// publication, as `valid_slot` may differ from `publish_slot`.
+    fn validity_duration(&self, current_slot: u64) -> u64 {
+        current_slot.saturating_sub(self.valid_slot())
+    }
+
+    /// Age in slots past which the feed advertises its price should be
+    /// rejected, zero if it doesn't advertise one.
+    fn max_staleness(&self) -> u64 {
+        self.buffer_slot(MAX_STALENESS_SLOT) as u64
+    }
+
+    /// Slots after publication before a price becomes valid, giving publishers
+    /// time for the update to be confirmed.
+    fn valid_slot_delay(&self) -> u64 {
+        self.buffer_slot(VALID_SLOT_DELAY_SLOT) as u64
+    }
+
+    /// Number of updates written since initialize, incremented by exactly one
+    /// per update so consumers can detect missed writes.
+    fn sequence(&self) -> u64 {
+        self.buffer_slot(SEQUENCE_SLOT) as u64
+    }
+
+    /// Lifetime number of successful updates, for analytics. Unlike
+    /// [OracleState::sequence] it saturates rather than failing updates.
+    fn update_count(&self) -> u64 {
+        self.buffer_slot(UPDATE_COUNT_SLOT) as u64
+    }
+
+    /// Whether the price is older than the feed's advertised
+    /// [OracleState::max_staleness] at `current_slot`. Never expires when no
+    /// maximum is configured.
+    fn is_expired(&self, current_slot: u64) -> bool {
+        match self.max_staleness() {
+            0 => false,
+            max_staleness => !self.is_fresh(current_slot, max_staleness),
+        }
+    }
+
+    /// Whether the price moved further than its own confidence interval since
+    /// the previous update, a sign of an anomalous tick.
+    fn moved_beyond_confidence(&self) -> bool {
+        let moved = (self.price_component() as i128 - self.previous_price_component() as i128)
+            .unsigned_abs();
+        moved > self.confidence_component() as u128
+    }
+
+    /// `(price - confidence, price + confidence)`, saturating at the bounds of
+    /// `i64`.
+    fn price_band(&self) -> (i64, i64) {
+        let price = self.price_component();
+        let confidence = i64::try_from(self.confidence_component()).unwrap_or(i64::MAX);
+        (price.saturating_sub(confidence), price.saturating_add(confidence))
+    }
+
+    /// Change from the previous price to the current one, in basis points of
+    /// the previous price, saturating at the bounds of `i64`. `None` when the
+    /// previous price is zero.
+    fn price_change_bps(&self) -> Option<i64> {
+        let previous = self.previous_price_component() as i128;
+        if previous == 0 {
+            return None;
+        }
+        let change = (self.price_component() as i128 - previous)
+            .checked_mul(BPS_DENOMINATOR as i128)?
+            / previous.abs();
+        Some(change.clamp(i64::MIN as i128, i64::MAX as i128) as i64)
+    }
+
+    /// Whether the oracle has been updated since initialize, as opposed to
+    /// holding only the price it was initialized with.
+    fn has_price(&self) -> bool {
+        self.flags().contains(OracleFlags::HAS_PRICE)
+    }
+
+    /// Whether the price was published at most `max_age_slots` before
+    /// `current_slot`. A `publish_slot` ahead of `current_slot` counts as fresh.
+    fn is_fresh(&self, current_slot: u64, max_age_slots: u64) -> bool {
+        current_slot.saturating_sub(self.publish_slot()) <= max_age_slots
+    }
+
+    /// Confidence widened by `widen_per_slot` for every slot the price has aged
+    /// at `current_slot`, saturating at `u64::MAX`. A `publish_slot` ahead of
+    /// `current_slot` isn't widened.
+    fn confidence_with_staleness(&self, current_slot: u64, widen_per_slot: u64) -> u64 {
+        let age_slots = current_slot.saturating_sub(self.publish_slot());
+        self.confidence_component()
+            .saturating_add(age_slots.saturating_mul(widen_per_slot))
+    }
+
+    /// `(lower, mid, upper)` of the confidence band with the exponent applied,
+    /// or `None` unless the price is fresh per [OracleState::is_fresh] and the
+    /// status is `Trading`.
+    fn scaled_band_and_status(&self, current_slot: u64, max_age: u64) -> Option<(f64, f64, f64)> {
+        if self.status() != u32::from(PriceStatus::Trading) || !self.is_fresh(current_slot, max_age) {
+            return None;
+        }
+        let scale = pow10(self.exponent());
+        let (lower, upper) = self.price_band();
+        Some((
+            lower as f64 * scale,
+            self.price_component() as f64 * scale,
+            upper as f64 * scale,
+        ))
+    }
+
+    /// Whether the price is within `tolerance_bps` basis points of `reference`,
+    /// where `reference` is expressed at the oracle's exponent. A zero reference
+    /// only matches a zero price, and any overflow is treated as out of tolerance.
+    fn within_tolerance(&self, reference: i64, tolerance_bps: u64) -> bool {
+        let diff = (self.price_component() as i128 - reference as i128).unsigned_abs();
+        let allowed = (reference.unsigned_abs() as u128).checked_mul(tolerance_bps as u128);
+        match (diff.checked_mul(BPS_DENOMINATOR as u128), allowed) {
+            (Some(diff), Some(allowed)) => diff <= allowed,
+            _ => false,
+        }
+    }
+
+    /// Inverse of the scaled price, flipping quote/base to base/quote. `None`
+    /// when the price is zero.
+    fn inverse_price(&self) -> Option<f64> {
+        match self.price_component() {
+            0 => None,
+            price => Some(1.0 / (price as f64 * pow10(self.exponent()))),
+        }
+    }
+
+    /// Inverse of the price as an integer at `target_exponent`, rounded toward
+    /// zero. `None` when the price is zero or the result doesn't fit an `i64`.
+    fn inverse_price_scaled(&self, target_exponent: i32) -> Option<i64> {
+        let price = self.price_component() as i128;
+        if price == 0 {
+            return None;
+        }
+        // 1 / (price * 10^exponent) = 10^(-exponent - target_exponent) / price * 10^target_exponent
+        let exponent = (self.exponent() as i64)
+            .checked_add(target_exponent as i64)?
+            .checked_neg()?;
+        if exponent < 0 {
+            // the inverse is below one unit at the target exponent
+            return Some(0);
+        }
+        let numerator = apply_exponent(1, i32::try_from(exponent).ok()?)?;
+        i64::try_from(numerator / price).ok()
+    }
+
+    /// Price rescaled to `target_exponent`, rounded toward zero. `None` when
+    /// the result doesn't fit an `i64` or rescaling discards the whole price,
+    /// see [apply_exponent].
+    fn price_at_exponent(&self, target_exponent: i32) -> Option<i64> {
+        let shift = self.exponent().checked_sub(target_exponent)?;
+        i64::try_from(apply_exponent(self.price_component() as i128, shift)?).ok()
+    }
+
+    /// Largest price representable at the oracle's exponent.
+    fn max_scaled_price(&self) -> f64 {
+        i64::MAX as f64 * pow10(self.exponent())
+    }
+
+    /// Smallest (most negative) price representable at the oracle's exponent.
+    fn min_scaled_price(&self) -> f64 {
+        i64::MIN as f64 * pow10(self.exponent())
+    }
+
+    /// Divergence of the ema price from the spot price, in basis points of the
+    /// spot price. `None` when the spot price is zero.
+    fn ema_spot_divergence_bps(&self) -> Option<i64> {
+        let spot = self.price_component() as i128;
+        if spot == 0 {
+            return None;
+        }
+        let ema = self.ema_price_value() as i64 as i128;
+        i64::try_from((ema - spot) * BPS_DENOMINATOR as i128 / spot).ok()
+    }
+
+    /// Whether the spot price is within `max_deviation_bps` basis points of the
+    /// ema price, guarding against a manipulated spike. See
+    /// [OracleState::within_tolerance] for how zero and overflow are treated.
+    fn price_within_ema_band(&self, max_deviation_bps: u64) -> bool {
+        self.within_tolerance(self.ema_price_value() as i64, max_deviation_bps)
+    }
+
+    /// Number of fractional digits to render the price with, zero for
+    /// non-negative exponents.
+    fn display_decimals(&self) -> u32 {
+        self.exponent().min(0).unsigned_abs()
+    }
+
+    /// Price rendered as a decimal string, e.g. `123456789` with exponent `-8`
+    /// renders as `"1.23456789"`.
+    fn format_price(&self) -> String {
+        let price = self.price_component();
+        format_scaled(price < 0, price.unsigned_abs(), self.exponent())
+    }
+
+    /// Key fields rendered for operators, with the exponent applied to the
+    /// price and confidence, e.g.
+    /// `price 1.23456789 ± 0.00000100, status Trading, published at slot 42`.
+    fn describe(&self) -> String {
+        let status = match PriceStatus::try_from(self.status()) {
+            Ok(status) => format!("{:?}", status),
+            Err(_) => format!("Invalid({})", self.status()),
+        };
+        format!(
+            "price {} \u{b1} {}, status {}, published at slot {}",
+            self.format_price(),
+            format_scaled(false, self.confidence_component(), self.exponent()),
+            status,
+            self.publish_slot(),
+        )
+    }
+
+    /// Confidence as a percentage of the absolute price. `None` when the price
+    /// is zero.
+    fn confidence_pct(&self) -> Option<f64> {
+        match self.price_component().unsigned_abs() {
+            0 => None,
+            price => Some(self.confidence_component() as f64 / price as f64 * 100.0),
+        }
+    }
+
+    /// Confidence as an absolute value alongside
+    /// [OracleState::confidence_pct], which is `None` when the price is zero.
+    fn confidence_detail(&self) -> (u64, Option<f64>) {
+        (self.confidence_component(), self.confidence_pct())
+    }
+
+    /// Sha256 of the price, confidence, status and publish slot, each little
+    /// endian, for programs committing to an observed price.
+    fn state_commitment(&self) -> [u8; 32] {
+        hashv(&[
+            &self.price_component().to_le_bytes(),
+            &self.confidence_component().to_le_bytes(),
+            &self.status().to_le_bytes(),
+            &self.publish_slot().to_le_bytes(),
+        ])
+        .to_bytes()
+    }
+
+    /// Price as an unsigned Q64.64 fixed-point number. Negative prices map to
+    /// zero, prices too large to represent saturate at `u128::MAX`, and prices
+    /// too small for the fractional bits round down to zero.
+    fn price_q64(&self) -> u128 {
+        let price = match u128::try_from(self.price_component()) {
+            Ok(price) => price,
+            Err(_) => return 0,
+        };
+        // price < 2^63, so shifting by 64 bits can't overflow
+        let fixed = price << 64;
+        let exponent = self.exponent();
+        match 10u128.checked_pow(exponent.unsigned_abs()) {
+            Some(scale) if exponent < 0 => fixed / scale,
+            Some(scale) => fixed.saturating_mul(scale),
+            None if exponent < 0 => 0,
+            None if price == 0 => 0,
+            None => u128::MAX,
+        }
+    }
+
+    /// Price as an exact `(numerator, denominator)` pair for integer math: the
+    /// price over 10^-exponent for negative exponents, or the price times
+    /// 10^exponent over one otherwise. Powers of ten beyond `i128` saturate.
+    fn price_rational(&self) -> (i128, i128) {
+        let price = self.price_component() as i128;
+        let exponent = self.exponent();
+        let scale = 10i128.checked_pow(exponent.unsigned_abs()).unwrap_or(i128::MAX);
+        if exponent < 0 {
+            (price, scale)
+        } else {
+            (price.saturating_mul(scale), 1)
+        }
+    }
+}
+
+/// Blend of the scaled prices of two oracles, giving `a` a weight of
+/// `weight_a_bps` basis points and `b` the rest. `None` when the weight is
+/// above one whole or either scaled price isn't finite.
+pub fn weighted_mid(a: &dyn OracleState, b: &dyn OracleState, weight_a_bps: u64) -> Option<f64> {
+    if weight_a_bps > BPS_DENOMINATOR {
+        return None;
+    }
+    let scaled_a = a.price_component() as f64 * pow10(a.exponent());
+    let scaled_b = b.price_component() as f64 * pow10(b.exponent());
+    let weight_a = weight_a_bps as f64 / BPS_DENOMINATOR as f64;
+    let mid = scaled_a * weight_a + scaled_b * (1.0 - weight_a);
+    if mid.is_finite() {
+        Some(mid)
+    } else {
+        None
+    }
+}
+
+/// Chainlink-style accessors for consumers written against an aggregator
+/// interface, implemented for every [OracleState].
+#[cfg(feature = "chainlink-compat")]
+pub trait ChainlinkCompat: OracleState {
+    /// Latest round as `(round_id, answer, updated_at)`, mapped from
+    /// `publish_slot`, `price_component` and `timestamp` respectively.
+    fn latest_round_data(&self) -> (u64, i64, i64) {
+        (self.publish_slot(), self.price_component(), self.timestamp())
+    }
+}
+
+#[cfg(feature = "chainlink-compat")]
+impl<T: OracleState + ?Sized> ChainlinkCompat for T {}
+
+/// All versions of OracleState
+#[enum_dispatch(OracleState)]
+pub enum OracleVersion {
+    /// Latest version, used for all new oracle
+    OracleV1,
+    /// [OracleV1] with a full-width `i128` price, used for oracles initialized
+    /// in an account of [OracleV2::LEN] bytes
+    OracleV2,
 }
 
-/// All versions of OracleState
-#[enum_dispatch(OracleState)]
-pub enum OracleVersion {
-    /// Latest version, used for all new oracle
-    OracleV1,
+/// OracleVersion does not implement program_pack::Pack because there are size
+/// checks on pack and unpack that would break backwards compatibility, so
+/// special implementations are provided here
+impl OracleVersion {
+    /// Size of the latest version of the OracleState
+    pub const LATEST_LEN: usize = 1 + OracleV1::LEN; // add one for the version enum
+
+    /// Pack a oracle into a byte array, based on its version. Bytes past the
+    /// packed length of an oversized account are left untouched, so data
+    /// written by a later version of the program survives.
+    pub fn pack(src: Self, dst: &mut [u8]) -> Result<(), ProgramError> {
+        match src {
+            Self::OracleV1(oracle_info) => {
+                let dst = Self::packed_region(dst, OracleV1::LEN);
+                dst[0] = 1;
+                OracleV1::pack(oracle_info, &mut dst[0..])
+            }
+            Self::OracleV2(oracle) => OracleV2::pack(oracle, Self::packed_region(dst, OracleV2::LEN)),
+        }
+    }
+
+    /// The first `len` bytes of `dst`, or all of it if it isn't longer.
+    fn packed_region(dst: &mut [u8], len: usize) -> &mut [u8] {
+        match dst.len() {
+            dst_len if dst_len > len => &mut dst[..len],
+            _ => dst,
+        }
+    }
+
+    /// Read the version field from packed oracle data without unpacking the
+    /// rest of the account
+    pub fn peek_version(data: &[u8]) -> Result<u32, ProgramError> {
+        let version = data.get(4..8).ok_or(ProgramError::InvalidAccountData)?;
+        Ok(u32::from_le_bytes(*array_ref![version, 0, 4]))
+    }
+
+    /// Read the status field from packed oracle data, for gating on the status
+    /// without unpacking the rest of the account
+    pub fn read_status(data: &[u8]) -> Result<u32, ProgramError> {
+        let status = data.get(224..228).ok_or(ProgramError::InvalidAccountData)?;
+        Ok(u32::from_le_bytes(*array_ref![status, 0, 4]))
+    }
+
+    /// Read the price component from packed oracle data without unpacking the
+    /// rest of the account
+    pub fn read_price_raw(data: &[u8]) -> Result<i64, ProgramError> {
+        let price = data.get(208..216).ok_or(ProgramError::InvalidAccountData)?;
+        Ok(i64::from_le_bytes(*array_ref![price, 0, 8]))
+    }
+
+    /// Unpack the oracle account based on its version, returning the result as a
+    /// OracleState trait object
+    pub fn unpack(input: &[u8]) -> Result<Box<dyn OracleState>, ProgramError> {
+        match Self::peek_version(input)? {
+            VERSION => Ok(Box::new(Self::unpack_v1(input)?)),
+            VERSION_V2 => {
+                let oracle = OracleV2::unpack(input)?;
+                oracle.verify_size(input.len())?;
+                Ok(Box::new(oracle))
+            }
+            _ => Err(ProgramError::UninitializedAccount),
+        }
+    }
+
+    /// Unpack an oracle account the caller knows to be an [OracleV1], with the
+    /// same checks as [OracleVersion::unpack] but without boxing the result.
+    /// Accounts longer than [OracleV1::LEN] are treated as forward-compatible
+    /// padding: only the first `LEN` bytes are read.
+    pub fn unpack_v1(input: &[u8]) -> Result<OracleV1, ProgramError> {
+        let input = input.get(..OracleV1::LEN).unwrap_or(input);
+        let oracle = OracleV1::unpack(input)?;
+        oracle.verify_size(input.len())?;
+        Ok(oracle)
+    }
+
+    /// Unpack an [OracleV1] account into `out` in place, with the same checks
+    /// as [OracleVersion::unpack_v1], for callers that can't afford another
+    /// copy of the struct on the stack or heap. `out` is unspecified on error.
+    pub fn unpack_into(input: &[u8], out: &mut OracleV1) -> Result<(), ProgramError> {
+        let input = input.get(..OracleV1::LEN).unwrap_or(input);
+        let oracle = OracleStateRef::new(input)?;
+        out.magic = oracle.magic();
+        out.version = oracle.version();
+        out.acctype = oracle.acctype();
+        out.size = oracle.size();
+        out.price_type = oracle.price_type();
+        out.exponent = oracle.exponent();
+        out.num_component_prices = oracle.num_component_prices();
+        out.num_quoters = oracle.num_quoters();
+        out.last_slot = oracle.last_slot();
+        out.valid_slot = oracle.valid_slot();
+        out.ema_price_value = oracle.ema_price_value();
+        out.ema_price_numerator = oracle.ema_price_numerator();
+        out.ema_price_denominator = oracle.ema_price_denominator();
+        out.ema_confidence_value = oracle.ema_confidence_value();
+        out.ema_confidence_numerator = oracle.ema_confidence_numerator();
+        out.ema_confidence_denominator = oracle.ema_confidence_denominator();
+        out.timestamp = oracle.timestamp();
+        out.min_publishers = oracle.min_publishers();
+        out.drv2 = oracle.drv2();
+        out.drv3 = oracle.drv3();
+        out.drv4 = oracle.drv4();
+        out.product_account_key = *oracle.product_account_key();
+        out.next_price_account_key = *oracle.next_price_account_key();
+        out.previous_slot = oracle.previous_slot();
+        out.previous_price_component = oracle.previous_price_component();
+        out.previous_confidence_component = oracle.previous_confidence_component();
+        out.previous_timestamp = oracle.previous_timestamp();
+        out.price_component = oracle.price_component();
+        out.confidence_component = oracle.confidence_component();
+        out.status = oracle.status();
+        out.corporate_action = oracle.corporate_action();
+        out.publish_slot = oracle.publish_slot();
+        for (index, slot) in out.buffer.iter_mut().enumerate() {
+            *slot = oracle.buffer_slot(index);
+        }
+        if !out.is_initialized() {
+            return Err(ProgramError::UninitializedAccount);
+        }
+        out.verify_size(input.len())
+    }
+}
+
+/// Program states.
+#[repr(C)]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct OracleV1 {
+    /// magic number.
+    pub magic: u32,
+    /// program version.
+    pub version: u32,
+    /// account type.
+    pub acctype: u32,
+    /// account size.
+    pub size: u32,
+    /// price type.
+    pub price_type: u32,
+    /// price exponent.
+    pub exponent: i32,
+    /// number of component prices.
+    pub num_component_prices: u32,
+    /// number of quoters that make up aggregate. Set at initialize and then
+    /// tracks the allowlisted publishers, never dropping below one.
+    pub num_quoters: u32,
+    /// slot of last valid aggregate price.
+    pub last_slot: u64,
+    /// valid slot-time of agg.
+    pub valid_slot: u64,
+    /// exponentially moving average price.
+    pub ema_price_value: u64,
+    /// numerator state for moving average price.
+    pub ema_price_numerator: u64,
+    /// denominator state for moving average price.
+    pub ema_price_denominator: u64,
+    /// exponentially moving average confidence interval.
+    pub ema_confidence_value: u64,
+    /// numerator state for moving average confidence.
+    pub ema_confidence_numerator: u64,
+    /// denominator state for moving average confidence.
+    pub ema_confidence_denominator: u64,
+    /// unix timestamp of aggregate price.
+    pub timestamp: i64,
+    /// min publishers for valid price.
+    pub min_publishers: u8,
+    /// decimals of the quote token.
+    pub drv2: i8,
+    /// decimals of the base token.
+    pub drv3: i16,
+    /// feature flags, see [OracleFlags].
+    pub drv4: i32,
+    /// product account key.
+    pub product_account_key: Pubkey,
+    /// next Price account in linked list.
+    pub next_price_account_key: Pubkey,
+    /// valid slot of previous update.
+    pub previous_slot: u64,
+    /// aggregate price of previous update with TRADING status.
+    pub previous_price_component: i64,
+    /// confidence interval of previous update with TRADING status.
+    pub previous_confidence_component: u64,
+    /// unix timestamp of previous aggregate with TRADING status.
+    pub previous_timestamp: i64,
+    /// the current price.
+    pub price_component: i64,
+    /// confidence interval around the price.
+    pub confidence_component: u64,
+    /// status of price.
+    pub status: u32,
+    /// notification of any corporate action.
+    pub corporate_action: u32,
+    /// publish slot.
+    pub publish_slot: u64,
+    /// price components one per quoter.
+    pub buffer: [u128;192],
+}
+
+impl OracleState for OracleV1 {
+    fn magic(&self) -> u32 {
+        self.magic
+    }
+
+    fn version(&self) -> u32 {
+        self.version
+    }
+
+    fn acctype(&self) -> u32 {
+        self.acctype
+    }
+    
+    fn size(&self) -> u32 {
+        self.size
+    }
+    
+    fn price_type(&self) -> u32 {
+        self.price_type
+    }
+
+    fn exponent(&self) -> i32 {
+        self.exponent
+    }
+
+    fn num_component_prices(&self) -> u32 {
+        self.num_component_prices
+    }
+
+    fn num_quoters(&self) -> u32 {
+        self.num_quoters
+    }
+
+    fn last_slot(&self) -> u64 {
+        self.last_slot
+    }
+
+    fn valid_slot(&self) -> u64 {
+        self.valid_slot
+    }
+
+    fn ema_price_value(&self) -> u64 {
+        self.ema_price_value
+    }
+
+    fn ema_price_numerator(&self) -> u64 {
+        self.ema_price_numerator
+    }
+
+    fn ema_price_denominator(&self) -> u64 {
+        self.ema_price_denominator
+    }
+
+    fn ema_confidence_value(&self) -> u64 {
+        self.ema_confidence_value
+    }
+
+    fn ema_confidence_numerator(&self) -> u64 {
+        self.ema_confidence_numerator
+    }
+
+    fn ema_confidence_denominator(&self) -> u64 {
+        self.ema_confidence_denominator
+    }
+
+    fn timestamp(&self) -> i64 {
+        self.timestamp
+    }
+
+    fn min_publishers(&self) -> u8 {
+        self.min_publishers
+    }
+
+    fn drv2(&self) -> i8 {
+        self.drv2
+    }
+
+    fn drv3(&self) -> i16 {
+        self.drv3
+    }
+
+    fn drv4(&self) -> i32 {
+        self.drv4
+    }
+
+    fn product_account_key(&self) -> &Pubkey {
+        &self.product_account_key
+    }
+
+    fn next_price_account_key(&self) -> &Pubkey {
+        &self.next_price_account_key
+    }
+
+    fn previous_slot(&self) -> u64 {
+        self.previous_slot
+    }
+
+    fn previous_price_component(&self) -> i64 {
+        self.previous_price_component
+    }
+
+    fn previous_confidence_component(&self) -> u64 {
+        self.previous_confidence_component
+    }
+
+    fn previous_timestamp(&self) -> i64 {
+        self.previous_timestamp
+    }
+
+    fn price_component(&self) -> i64 {
+        self.price_component
+    }
+
+    fn confidence_component(&self) -> u64 {
+        self.confidence_component
+    }
+
+    fn status(&self) -> u32 {
+        self.status
+    }
+
+    fn corporate_action(&self) -> u32 {
+        self.corporate_action
+    }
+
+    fn publish_slot(&self) -> u64 {
+        self.publish_slot
+    }
+
+    fn buffer(&self) -> [u128;192] {
+        self.buffer
+    }
+
+    fn buffer_slot(&self, index: usize) -> u128 {
+        self.buffer[index]
+    }
+}
+
+impl OracleV1 {
+    /// Whether `data` is entirely zero, as a never-written or cleared account.
+    pub fn is_zeroed(data: &[u8]) -> bool {
+        data.iter().all(|&byte| byte == 0)
+    }
+
+    /// Hash of the packed layout, see [SCHEMA_HASH].
+    pub const fn schema_hash() -> [u8; 32] {
+        SCHEMA_HASH
+    }
+
+    /// The packed on-chain bytes of this oracle.
+    pub fn to_bytes(&self) -> [u8; OracleV1::LEN] {
+        let mut bytes = [0; OracleV1::LEN];
+        self.pack_into_slice(&mut bytes);
+        bytes
+    }
+
+    /// Anchor account discriminator, see [ACCOUNT_DISCRIMINATOR].
+    pub const fn discriminator() -> [u8; 8] {
+        ACCOUNT_DISCRIMINATOR
+    }
+
+    /// Pack every field except the buffer, leaving the buffer region of
+    /// `output` untouched.
+    pub fn pack_header_into_slice(&self, output: &mut [u8]) {
+        let output = array_mut_ref![output, 0, HEADER_LEN];
+        let (
+            magic,
+            version,
+            acctype,
+            size,
+            price_type,
+            exponent,
+            num_component_prices,
+            num_quoters,
+            last_slot,
+            valid_slot,
+            ema_price_value,
+            ema_price_numerator,
+            ema_price_denominator,
+            ema_confidence_value,
+            ema_confidence_numerator,
+            ema_confidence_denominator,
+            timestamp,
+            min_publishers,
+            drv2,
+            drv3,
+            drv4,
+            product_account_key,
+            next_price_account_key,
+            previous_slot,
+            previous_price_component,
+            previous_confidence_component,
+            previous_timestamp,
+            price_component,
+            confidence_component,
+            status,
+            corporate_action,
+            publish_slot,
+        ) = mut_array_refs![output, 4, 4, 4, 4, 4, 4, 4, 4, 8, 8, 8, 8, 8, 8, 8, 8, 8, 1, 1, 2, 4, 32, 32, 8, 8, 8, 8, 8, 8, 4, 4, 8];
+        *magic = self.magic.to_le_bytes();
+        *version = self.version.to_le_bytes();
+        *acctype = self.acctype.to_le_bytes();
+        *size = self.size.to_le_bytes();
+        *price_type = self.price_type.to_le_bytes();
+        *exponent = self.exponent.to_le_bytes();
+        *num_component_prices = self.num_component_prices.to_le_bytes();
+        *num_quoters = self.num_quoters.to_le_bytes();
+        *last_slot = self.last_slot.to_le_bytes();
+        *valid_slot = self.valid_slot.to_le_bytes();
+        *ema_price_value = self.ema_price_value.to_le_bytes();
+        *ema_price_numerator = self.ema_price_numerator.to_le_bytes();
+        *ema_price_denominator = self.ema_price_denominator.to_le_bytes();
+        *ema_confidence_value = self.ema_confidence_value.to_le_bytes();
+        *ema_confidence_numerator = self.ema_confidence_numerator.to_le_bytes();
+        *ema_confidence_denominator = self.ema_confidence_denominator.to_le_bytes();
+        *timestamp = self.timestamp.to_le_bytes();
+        *min_publishers = self.min_publishers.to_le_bytes();
+        *drv2 = self.drv2.to_le_bytes();
+        *drv3 = self.drv3.to_le_bytes();
+        *drv4 = self.drv4.to_le_bytes();
+        product_account_key.copy_from_slice(self.product_account_key.as_ref());
+        next_price_account_key.copy_from_slice(self.next_price_account_key.as_ref());
+        *previous_slot = self.previous_slot.to_le_bytes();
+        *previous_price_component = self.previous_price_component.to_le_bytes();
+        *previous_confidence_component = self.previous_confidence_component.to_le_bytes();
+        *previous_timestamp = self.previous_timestamp.to_le_bytes();
+        *price_component = self.price_component.to_le_bytes();
+        *confidence_component = self.confidence_component.to_le_bytes();
+        *status = self.status.to_le_bytes();
+        *corporate_action = self.corporate_action.to_le_bytes();
+        *publish_slot = self.publish_slot.to_le_bytes();
+    }
+
+    /// Pack only buffer entry `index`, leaving the rest of `output` untouched.
+    pub fn pack_buffer_slot_into_slice(&self, index: usize, output: &mut [u8]) {
+        *array_mut_ref![output, HEADER_LEN + 16 * index, 16] = self.buffer[index].to_le_bytes();
+    }
+
+    /// Append a `(price, confidence, slot)` sample to the price history,
+    /// overwriting the oldest sample once the ring is full.
+    pub fn push_history(&mut self, price: i64, confidence: u64, slot: u64) {
+        let cursor = self.history_cursor();
+        let index = (cursor % HISTORY_LEN as u64) as usize;
+        self.buffer[HISTORY_SLOT + 2 * index] = (price as u64 as u128) | ((confidence as u128) << 64);
+        self.buffer[HISTORY_SLOT + 2 * index + 1] = slot as u128;
+        self.buffer[HISTORY_CURSOR_SLOT] = cursor.wrapping_add(1) as u128;
+    }
+
+    /// Record a component `(price, confidence)` at `index` along with the
+    /// publisher that submitted it.
+    pub fn set_component(&mut self, index: usize, publisher: &Pubkey, price: i64, confidence: u64) {
+        let (lo, hi) = pubkey_to_slots(publisher);
+        self.buffer[COMPONENT_SLOT + index] = (price as u64 as u128) | ((confidence as u128) << 64);
+        self.buffer[PUBLISHER_SLOT + 2 * index] = lo;
+        self.buffer[PUBLISHER_SLOT + 2 * index + 1] = hi;
+    }
+
+    /// Allowlist `publisher` for the component at `index`, or clear the slot
+    /// with the default pubkey.
+    pub fn set_component_publisher(&mut self, index: usize, publisher: &Pubkey) {
+        let (lo, hi) = pubkey_to_slots(publisher);
+        self.buffer[PUBLISHER_SLOT + 2 * index] = lo;
+        self.buffer[PUBLISHER_SLOT + 2 * index + 1] = hi;
+    }
+
+    /// Set the authority allowed to sign updates on the oracle's behalf.
+    pub fn set_authority(&mut self, authority: &Pubkey) {
+        let (lo, hi) = pubkey_to_slots(authority);
+        self.buffer[AUTHORITY_SLOT] = lo;
+        self.buffer[AUTHORITY_SLOT + 1] = hi;
+    }
+
+    /// Set the bid and ask of the last quote.
+    pub fn set_quote(&mut self, bid: i64, ask: i64) {
+        self.buffer[QUOTE_SLOT] = bid as u64 as u128 | (ask as u64 as u128) << 64;
+    }
+
+    /// Set the authority proposed to take over the oracle.
+    pub fn set_pending_authority(&mut self, authority: &Pubkey) {
+        let (lo, hi) = pubkey_to_slots(authority);
+        self.buffer[PENDING_AUTHORITY_SLOT] = lo;
+        self.buffer[PENDING_AUTHORITY_SLOT + 1] = hi;
+    }
+
+    /// Set the operator-defined metadata blob.
+    pub fn set_metadata(&mut self, metadata: &[u8; 32]) {
+        let (lo, hi) = pubkey_to_slots(&Pubkey::new_from_array(*metadata));
+        self.buffer[METADATA_SLOT] = lo;
+        self.buffer[METADATA_SLOT + 1] = hi;
+    }
+}
+
+/// Oracle state for assets whose price doesn't fit an `i64` at the oracle's
+/// exponent: the [OracleV1] layout followed by the full-width price.
+/// Consumers reading only the [OracleV1] prefix see the price saturated to
+/// an `i64`, and the ema and price history track that saturated price.
+#[repr(C)]
+#[derive(Debug, PartialEq)]
+pub struct OracleV2 {
+    /// fields shared with [OracleV1], `price_component` saturated from `price`.
+    pub base: OracleV1,
+    /// full-width aggregate price.
+    pub price: i128,
+}
+
+impl OracleState for OracleV2 {
+    fn magic(&self) -> u32 {
+        self.base.magic()
+    }
+
+    fn version(&self) -> u32 {
+        self.base.version()
+    }
+
+    fn acctype(&self) -> u32 {
+        self.base.acctype()
+    }
+
+    fn size(&self) -> u32 {
+        self.base.size()
+    }
+
+    fn price_type(&self) -> u32 {
+        self.base.price_type()
+    }
+
+    fn exponent(&self) -> i32 {
+        self.base.exponent()
+    }
+
+    fn num_component_prices(&self) -> u32 {
+        self.base.num_component_prices()
+    }
+
+    fn num_quoters(&self) -> u32 {
+        self.base.num_quoters()
+    }
+
+    fn last_slot(&self) -> u64 {
+        self.base.last_slot()
+    }
+
+    fn valid_slot(&self) -> u64 {
+        self.base.valid_slot()
+    }
+
+    fn ema_price_value(&self) -> u64 {
+        self.base.ema_price_value()
+    }
+
+    fn ema_price_numerator(&self) -> u64 {
+        self.base.ema_price_numerator()
+    }
+
+    fn ema_price_denominator(&self) -> u64 {
+        self.base.ema_price_denominator()
+    }
+
+    fn ema_confidence_value(&self) -> u64 {
+        self.base.ema_confidence_value()
+    }
+
+    fn ema_confidence_numerator(&self) -> u64 {
+        self.base.ema_confidence_numerator()
+    }
+
+    fn ema_confidence_denominator(&self) -> u64 {
+        self.base.ema_confidence_denominator()
+    }
+
+    fn timestamp(&self) -> i64 {
+        self.base.timestamp()
+    }
+
+    fn min_publishers(&self) -> u8 {
+        self.base.min_publishers()
+    }
+
+    fn drv2(&self) -> i8 {
+        self.base.drv2()
+    }
+
+    fn drv3(&self) -> i16 {
+        self.base.drv3()
+    }
+
+    fn drv4(&self) -> i32 {
+        self.base.drv4()
+    }
+
+    fn product_account_key(&self) -> &Pubkey {
+        self.base.product_account_key()
+    }
+
+    fn next_price_account_key(&self) -> &Pubkey {
+        self.base.next_price_account_key()
+    }
+
+    fn previous_slot(&self) -> u64 {
+        self.base.previous_slot()
+    }
+
+    fn previous_price_component(&self) -> i64 {
+        self.base.previous_price_component()
+    }
+
+    fn previous_confidence_component(&self) -> u64 {
+        self.base.previous_confidence_component()
+    }
+
+    fn previous_timestamp(&self) -> i64 {
+        self.base.previous_timestamp()
+    }
+
+    fn price_component(&self) -> i64 {
+        self.base.price_component()
+    }
+
+    fn confidence_component(&self) -> u64 {
+        self.base.confidence_component()
+    }
+
+    fn status(&self) -> u32 {
+        self.base.status()
+    }
+
+    fn corporate_action(&self) -> u32 {
+        self.base.corporate_action()
+    }
+
+    fn publish_slot(&self) -> u64 {
+        self.base.publish_slot()
+    }
+
+    fn buffer(&self) -> [u128;192] {
+        self.base.buffer()
+    }
+
+    fn buffer_slot(&self, index: usize) -> u128 {
+        self.base.buffer_slot(index)
+    }
+
+    fn price_i128(&self) -> i128 {
+        self.price
+    }
 }
 
-/// OracleVersion does not implement program_pack::Pack because there are size
-/// checks on pack and unpack that would break backwards compatibility, so
-/// special implementations are provided here
-impl OracleVersion {
-    /// Size of the latest version of the OracleState
-    pub const LATEST_LEN: usize = 1 + OracleV1::LEN; // add one for the version enum
+/// Borrowed view over a packed [OracleV1](struct.OracleV1.html) account,
+/// reading each field straight from the account data without allocating. The
+/// two account keys are copied out up front, since the trait lends them by
+/// reference.
+#[derive(Clone, Copy, Debug)]
+pub struct OracleStateRef<'a> {
+    data: &'a [u8; OracleV1::LEN],
+    product_account_key: Pubkey,
+    next_price_account_key: Pubkey,
+}
 
-    /// Pack a oracle into a byte array, based on its version
-    pub fn pack(src: Self, dst: &mut [u8]) -> Result<(), ProgramError> {
-        match src {
-            Self::OracleV1(oracle_info) => {
-                dst[0] = 1;
-                OracleV1::pack(oracle_info, &mut dst[0..])
-            }
+impl<'a> OracleStateRef<'a> {
+    /// Wrap packed oracle data, checking only that the length is correct
+    pub fn new(input: &'a [u8]) -> Result<Self, ProgramError> {
+        if input.len() != OracleV1::LEN {
+            return Err(ProgramError::InvalidAccountData);
         }
+        let data = array_ref![input, 0, OracleV1::LEN];
+        Ok(Self {
+            data,
+            product_account_key: Pubkey::new_from_array(*array_ref![data, 112, 32]),
+            next_price_account_key: Pubkey::new_from_array(*array_ref![data, 144, 32]),
+        })
     }
 
-    /// Unpack the oracle account based on its version, returning the result as a
-    /// OracleState trait object
-    pub fn unpack(input: &[u8]) -> Result<Box<dyn OracleState>, ProgramError> {
-        // let (&version, rest) = input;
-        let version = 2;
-        match version {
-            2 => Ok(Box::new(OracleV1::unpack(input)?)),
-            _ => Err(ProgramError::UninitializedAccount),
-        }
+    fn read_u32(&self, offset: usize) -> u32 {
+        u32::from_le_bytes(*array_ref![self.data, offset, 4])
     }
-}
 
-/// Program states.
-#[repr(C)]
-#[derive(Debug, PartialEq)]
-pub struct OracleV1 {
-    /// magic number.
-    pub magic: u32,
-    /// program version.
-    pub version: u32,
-    /// account type.
-    pub acctype: u32,
-    /// account size.
-    pub size: u32,
-    /// price type.
-    pub price_type: u32,
-    /// price exponent.
-    pub exponent: i32,
-    /// number of component prices.
-    pub num_component_prices: u32,
-    /// number of quoters that make up aggregate.
-    pub num_quoters: u32,
-    /// slot of last valid aggregate price.
-    pub last_slot: u64,
-    /// valid slot-time of agg.
-    pub valid_slot: u64,
-    /// exponentially moving average price.
-    pub ema_price_value: u64,
-    /// numerator state for moving average price.
-    pub ema_price_numerator: u64,
-    /// denominator state for moving average price.
-    pub ema_price_denominator: u64,
-    /// exponentially moving average confidence interval.
-    pub ema_confidence_value: u64,
-    /// numerator state for moving average confidence.
-    pub ema_confidence_numerator: u64,
-    /// denominator state for moving average confidence.
-    pub ema_confidence_denominator: u64,
-    /// unix timestamp of aggregate price.
-    pub timestamp: i64,
-    /// min publishers for valid price.
-    pub min_publishers: u8,
-    /// space for future derived values.
-    pub drv2: i8,
-    /// space for future derived values.
-    pub drv3: i16,
-    /// space for future derived values.
-    pub drv4: i32,
-    /// product account key.
-    pub product_account_key: Pubkey,
-    /// next Price account in linked list.
-    pub next_price_account_key: Pubkey,
-    /// valid slot of previous update.
-    pub previous_slot: u64,
-    /// aggregate price of previous update with TRADING status.
-    pub previous_price_component: i64,
-    /// confidence interval of previous update with TRADING status.
-    pub previous_confidence_component: u64,
-    /// unix timestamp of previous aggregate with TRADING status.
-    pub previous_timestamp: i64,
-    /// the current price.
-    pub price_component: i64,
-    /// confidence interval around the price.
-    pub confidence_component: u64,
-    /// status of price.
-    pub status: u32,
-    /// notification of any corporate action.
-    pub corporate_action: u32,
-    /// publish slot.
-    pub publish_slot: u64,
-    /// price components one per quoter.
-    pub buffer: [u128;192],
+    fn read_i32(&self, offset: usize) -> i32 {
+        i32::from_le_bytes(*array_ref![self.data, offset, 4])
+    }
+
+    fn read_u64(&self, offset: usize) -> u64 {
+        u64::from_le_bytes(*array_ref![self.data, offset, 8])
+    }
+
+    fn read_i64(&self, offset: usize) -> i64 {
+        i64::from_le_bytes(*array_ref![self.data, offset, 8])
+    }
 }
 
-impl OracleState for OracleV1 {
+impl<'a> OracleState for OracleStateRef<'a> {
     fn magic(&self) -> u32 {
-        self.magic
+        self.read_u32(0)
     }
 
     fn version(&self) -> u32 {
-        self.version
+        self.read_u32(4)
     }
 
     fn acctype(&self) -> u32 {
-        self.acctype
+        self.read_u32(8)
     }
-    
+
     fn size(&self) -> u32 {
-        self.size
+        self.read_u32(12)
     }
-    
+
     fn price_type(&self) -> u32 {
-        self.price_type
+        self.read_u32(16)
     }
 
     fn exponent(&self) -> i32 {
-        self.exponent
+        self.read_i32(20)
     }
 
     fn num_component_prices(&self) -> u32 {
-        self.num_component_prices
+        self.read_u32(24)
     }
 
     fn num_quoters(&self) -> u32 {
-        self.num_quoters
+        self.read_u32(28)
     }
 
     fn last_slot(&self) -> u64 {
-        self.last_slot
+        self.read_u64(32)
     }
 
     fn valid_slot(&self) -> u64 {
-        self.valid_slot
+        self.read_u64(40)
     }
 
     fn ema_price_value(&self) -> u64 {
-        self.ema_price_value
+        self.read_u64(48)
     }
 
     fn ema_price_numerator(&self) -> u64 {
-        self.ema_price_numerator
+        self.read_u64(56)
     }
 
     fn ema_price_denominator(&self) -> u64 {
-        self.ema_price_denominator
+        self.read_u64(64)
     }
 
     fn ema_confidence_value(&self) -> u64 {
-        self.ema_confidence_value
+        self.read_u64(72)
     }
 
     fn ema_confidence_numerator(&self) -> u64 {
-        self.ema_confidence_numerator
+        self.read_u64(80)
     }
 
     fn ema_confidence_denominator(&self) -> u64 {
-        self.ema_confidence_denominator
+        self.read_u64(88)
     }
 
     fn timestamp(&self) -> i64 {
-        self.timestamp
+        self.read_i64(96)
     }
 
     fn min_publishers(&self) -> u8 {
-        self.min_publishers
+        self.data[104]
     }
 
     fn drv2(&self) -> i8 {
-        self.drv2
+        self.data[105] as i8
     }
 
     fn drv3(&self) -> i16 {
-        self.drv3
+        i16::from_le_bytes(*array_ref![self.data, 106, 2])
     }
 
     fn drv4(&self) -> i32 {
-        self.drv4
+        self.read_i32(108)
     }
 
     fn product_account_key(&self) -> &Pubkey {
@@ -280,43 +1870,154 @@ impl OracleState for OracleV1 {
     }
 
     fn previous_slot(&self) -> u64 {
-        self.previous_slot
+        self.read_u64(176)
     }
 
     fn previous_price_component(&self) -> i64 {
-        self.previous_price_component
+        self.read_i64(184)
     }
 
     fn previous_confidence_component(&self) -> u64 {
-        self.previous_confidence_component
+        self.read_u64(192)
     }
 
     fn previous_timestamp(&self) -> i64 {
-        self.previous_timestamp
+        self.read_i64(200)
     }
 
     fn price_component(&self) -> i64 {
-        self.price_component
+        self.read_i64(208)
     }
 
     fn confidence_component(&self) -> u64 {
-        self.confidence_component
+        self.read_u64(216)
     }
 
     fn status(&self) -> u32 {
-        self.status
+        self.read_u32(224)
     }
 
     fn corporate_action(&self) -> u32 {
-        self.corporate_action
+        self.read_u32(228)
     }
 
     fn publish_slot(&self) -> u64 {
-        self.publish_slot
+        self.read_u64(232)
     }
 
     fn buffer(&self) -> [u128;192] {
-        self.buffer
+        let mut buffer = [0u128; 192];
+        for (i, slot) in buffer.iter_mut().enumerate() {
+            *slot = self.buffer_slot(i);
+        }
+        buffer
+    }
+
+    fn buffer_slot(&self, index: usize) -> u128 {
+        u128::from_le_bytes(*array_ref![self.data, 240 + index * 16, 16])
+    }
+}
+
+/// Program-wide configuration, stored in the PDA at [find_config_address].
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct ProgramConfig {
+    /// Whether the config has been written
+    pub is_initialized: bool,
+    /// Whether all updates are blocked
+    pub paused: bool,
+}
+
+impl OracleV2 {
+    /// Pack only the full-width price, leaving the [OracleV1] base of `output`
+    /// untouched.
+    pub fn pack_price_into_slice(price: i128, output: &mut [u8]) {
+        *array_mut_ref![output, OracleV1::LEN, 16] = price.to_le_bytes();
+    }
+}
+
+impl Sealed for OracleV2 {}
+
+impl IsInitialized for OracleV2 {
+    fn is_initialized(&self) -> bool {
+        self.base.is_initialized()
+    }
+}
+
+impl Pack for OracleV2 {
+    const LEN: usize = OracleV1::LEN + 16;
+
+    fn pack_into_slice(&self, output: &mut [u8]) {
+        let output = array_mut_ref![output, 0, OracleV2::LEN];
+        let (base, price) = mut_array_refs![output, OracleV1::LEN, 16];
+        self.base.pack_into_slice(base);
+        *price = self.price.to_le_bytes();
+    }
+
+    /// Unpacks a byte buffer into a [OracleV2](struct.OracleV2.html).
+    fn unpack_from_slice(input: &[u8]) -> Result<Self, ProgramError> {
+        let input = array_ref![input, 0, OracleV2::LEN];
+        let (base, price) = array_refs![input, OracleV1::LEN, 16];
+        Ok(Self {
+            base: OracleV1::unpack_from_slice(base)?,
+            price: i128::from_le_bytes(*price),
+        })
+    }
+}
+
+impl Sealed for ProgramConfig {}
+
+impl IsInitialized for ProgramConfig {
+    fn is_initialized(&self) -> bool {
+        self.is_initialized
+    }
+}
+
+impl Pack for ProgramConfig {
+    const LEN: usize = 2;
+
+    fn pack_into_slice(&self, output: &mut [u8]) {
+        let output = array_mut_ref![output, 0, 2];
+        output[0] = self.is_initialized as u8;
+        output[1] = self.paused as u8;
+    }
+
+    /// Unpacks a byte buffer into a [ProgramConfig](struct.ProgramConfig.html).
+    fn unpack_from_slice(input: &[u8]) -> Result<Self, ProgramError> {
+        let input = array_ref![input, 0, 2];
+        let to_bool = |byte: u8| match byte {
+            0 => Ok(false),
+            1 => Ok(true),
+            _ => Err(ProgramError::InvalidAccountData),
+        };
+        Ok(ProgramConfig {
+            is_initialized: to_bool(input[0])?,
+            paused: to_bool(input[1])?,
+        })
+    }
+}
+
+/// Recency ordering, not a price ordering: snapshots sort by `publish_slot`,
+/// then by [OracleState::sequence]. Remaining ties compare the packed header
+/// bytes and then the buffer so the order stays consistent with `Eq`.
+impl Ord for OracleV1 {
+    fn cmp(&self, other: &Self) -> Ordering {
+        (self.publish_slot, self.sequence())
+            .cmp(&(other.publish_slot, other.sequence()))
+            .then_with(|| {
+                let mut header = [0; HEADER_LEN];
+                let mut other_header = [0; HEADER_LEN];
+                self.pack_header_into_slice(&mut header);
+                other.pack_header_into_slice(&mut other_header);
+                header.cmp(&other_header)
+            })
+            .then_with(|| self.buffer.cmp(&other.buffer))
+    }
+}
+
+impl PartialOrd for OracleV1 {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
     }
 }
 
@@ -324,83 +2025,20 @@ impl Sealed for OracleV1 {}
 
 impl IsInitialized for OracleV1 {
     fn is_initialized(&self) -> bool {
-        false
+        self.magic == MAGIC
     }
 }
 
 impl Pack for OracleV1 {
-    const LEN: usize = 3312;
+    const LEN: usize = SIZE as usize;
     
     fn pack_into_slice(&self, output: &mut [u8]) {
         let output = array_mut_ref![output, 0, 3312];
-        let (
-            magic,
-            version,
-            acctype,
-            size,
-            price_type,
-            exponent,
-            num_component_prices,
-            num_quoters,
-            last_slot,
-            valid_slot,
-            ema_price_value,
-            ema_price_numerator,
-            ema_price_denominator,
-            ema_confidence_value,
-            ema_confidence_numerator,
-            ema_confidence_denominator,
-            timestamp,
-            min_publishers,
-            drv2,
-            drv3,
-            drv4,
-            product_account_key,
-            next_price_account_key,
-            previous_slot,
-            previous_price_component,
-            previous_confidence_component,
-            previous_timestamp,
-            price_component,
-            confidence_component,
-            status,
-            corporate_action,
-            publish_slot,
-            buffer,
-        ) = mut_array_refs![output, 4, 4, 4, 4, 4, 4, 4, 4, 8, 8, 8, 8, 8, 8, 8, 8, 8, 1, 1, 2, 4, 32, 32, 8, 8, 8, 8, 8, 8, 4, 4, 8, 3072];
-        *magic = self.magic.to_le_bytes();
-        *version = self.version.to_le_bytes();
-        *acctype = self.acctype.to_le_bytes();
-        *size = self.size.to_le_bytes();
-        *price_type = self.price_type.to_le_bytes();
-        *exponent = self.exponent.to_le_bytes();
-        *num_component_prices = self.num_component_prices.to_le_bytes();
-        *num_quoters = self.num_quoters.to_le_bytes();
-        *last_slot = self.last_slot.to_le_bytes();
-        *valid_slot = self.valid_slot.to_le_bytes();
-        *ema_price_value = self.ema_price_value.to_le_bytes();
-        *ema_price_numerator = self.ema_price_numerator.to_le_bytes();
-        *ema_price_denominator = self.ema_price_denominator.to_le_bytes();
-        *ema_confidence_value = self.ema_confidence_value.to_le_bytes();
-        *ema_confidence_numerator = self.ema_confidence_numerator.to_le_bytes();
-        *ema_confidence_denominator = self.ema_confidence_denominator.to_le_bytes();
-        *timestamp = self.timestamp.to_le_bytes();
-        *min_publishers = self.min_publishers.to_le_bytes();
-        *drv2 = self.drv2.to_le_bytes();
-        *drv3 = self.drv3.to_le_bytes();
-        *drv4 = self.drv4.to_le_bytes();
-        product_account_key.copy_from_slice(self.product_account_key.as_ref());
-        next_price_account_key.copy_from_slice(self.next_price_account_key.as_ref());
-        *previous_slot = self.previous_slot.to_le_bytes();
-        *previous_price_component = self.previous_price_component.to_le_bytes();
-        *previous_confidence_component = self.previous_confidence_component.to_le_bytes();
-        *previous_timestamp = self.previous_timestamp.to_le_bytes();
-        *price_component = self.price_component.to_le_bytes();
-        *confidence_component = self.confidence_component.to_le_bytes();
-        *status = self.status.to_le_bytes();
-        *corporate_action = self.corporate_action.to_le_bytes();
-        *publish_slot = self.publish_slot.to_le_bytes();
-        *buffer = [0;3072];
+        let (header, buffer) = mut_array_refs![output, HEADER_LEN, 3072];
+        self.pack_header_into_slice(header);
+        for (dst, slot) in buffer.chunks_exact_mut(16).zip(self.buffer.iter()) {
+            dst.copy_from_slice(&slot.to_le_bytes());
+        }
     }
 
     /// Unpacks a byte buffer into a [OracleV1](struct.OracleV1.html).
@@ -440,8 +2078,12 @@ impl Pack for OracleV1 {
             status,
             corporate_action,
             publish_slot,
-            _buffer,
-        ) = array_refs![input, 4, 4, 4, 4, 4, 4, 4, 4, 8, 8, 8, 8, 8, 8, 8, 8, 8, 1, 1, 2, 4, 32, 32, 8, 8, 8, 8, 8, 8, 4, 4, 8, 3072]; 
+            buffer,
+        ) = array_refs![input, 4, 4, 4, 4, 4, 4, 4, 4, 8, 8, 8, 8, 8, 8, 8, 8, 8, 1, 1, 2, 4, 32, 32, 8, 8, 8, 8, 8, 8, 4, 4, 8, 3072];
+        let mut buffer_slots = [0u128; 192];
+        for (slot, src) in buffer_slots.iter_mut().zip(buffer.chunks_exact(16)) {
+            *slot = u128::from_le_bytes(*array_ref![src, 0, 16]);
+        }
         Ok(Self {
             magic: u32::from_le_bytes(*magic),
             version: u32::from_le_bytes(*version),
@@ -475,7 +2117,7 @@ impl Pack for OracleV1 {
             status: u32::from_le_bytes(*status),
             corporate_action: u32::from_le_bytes(*corporate_action),
             publish_slot: u64::from_le_bytes(*publish_slot),
-            buffer: [0;192]
+            buffer: buffer_slots,
         })
     }
 }
@@ -0,0 +1,138 @@
+//! Accessors derived from the stored header fields
+
+mod common;
+
+use common::zeroed;
+use custom_oracle::state::{OracleFlags, OracleState, OracleV1};
+use solana_program::{program_error::ProgramError, program_pack::Pack, pubkey::Pubkey};
+
+#[test]
+fn test_zeroed_next_key_marks_list_tail() {
+    let mut oracle = zeroed();
+    assert!(oracle.is_list_tail());
+    oracle.next_price_account_key = Pubkey::new_unique();
+    assert!(!oracle.is_list_tail());
+}
+
+#[test]
+fn test_flags_round_trip_through_drv4() {
+    let mut oracle = zeroed();
+    assert_eq!(oracle.flags(), OracleFlags::empty());
+    let flags = OracleFlags::EMA_ENABLED | OracleFlags::SKIP_BUFFER;
+    oracle.drv4 = flags.bits() as i32;
+
+    let unpacked = OracleV1::unpack_unchecked(&oracle.to_bytes()).unwrap();
+    assert_eq!(unpacked.flags(), flags);
+    assert!(unpacked.flags().contains(OracleFlags::SKIP_BUFFER));
+    assert!(!unpacked.flags().contains(OracleFlags::FROZEN));
+}
+
+#[test]
+fn test_reserved_flag_bits() {
+    assert_eq!(OracleFlags::from_bits(1 << 31), None);
+    assert_eq!(OracleFlags::from_bits(1), Some(OracleFlags::EMA_ENABLED));
+    assert_eq!(OracleFlags::from_bits_truncate(1 << 31 | 1), OracleFlags::EMA_ENABLED);
+
+    // reserved bits stored in drv4 are ignored when read back
+    let mut oracle = zeroed();
+    oracle.drv4 = i32::MIN | OracleFlags::FROZEN.bits() as i32;
+    assert_eq!(oracle.flags(), OracleFlags::FROZEN);
+
+    let mut flags = OracleFlags::empty();
+    flags.insert(OracleFlags::FROZEN | OracleFlags::HAS_PRICE);
+    flags.remove(OracleFlags::FROZEN);
+    assert_eq!(flags, OracleFlags::HAS_PRICE);
+}
+
+#[test]
+fn test_validates_next() {
+    let product = Pubkey::new_unique();
+    let next_key = Pubkey::new_unique();
+    let mut head = zeroed();
+    head.product_account_key = product;
+    head.next_price_account_key = next_key;
+    let mut next = zeroed();
+    next.product_account_key = product;
+    assert!(head.validates_next(&next_key, &next));
+
+    // linked to a different account
+    assert!(!head.validates_next(&Pubkey::new_unique(), &next));
+    // a successor for another product
+    next.product_account_key = Pubkey::new_unique();
+    assert!(!head.validates_next(&next_key, &next));
+    // the tail has no successor
+    next.product_account_key = product;
+    head.next_price_account_key = Pubkey::default();
+    assert!(!head.validates_next(&Pubkey::default(), &next));
+}
+
+#[cfg(feature = "chainlink-compat")]
+#[test]
+fn test_latest_round_data_maps_fields() {
+    use custom_oracle::state::ChainlinkCompat;
+
+    let mut oracle = zeroed();
+    oracle.publish_slot = 42;
+    oracle.price_component = -1_000;
+    oracle.timestamp = 1_650_000_000;
+    assert_eq!(
+        oracle.latest_round_data(),
+        (oracle.publish_slot, oracle.price_component, oracle.timestamp)
+    );
+}
+
+#[test]
+fn test_prices_bundle_matches_getters() {
+    let mut oracle = zeroed();
+    oracle.exponent = -8;
+    oracle.price_component = 1_000_000;
+    oracle.confidence_component = 100;
+    oracle.ema_price_value = 990_000;
+    oracle.ema_confidence_value = 120;
+
+    let prices = oracle.prices();
+    assert_eq!(prices.spot, oracle.price_component());
+    assert_eq!(prices.spot_conf, oracle.confidence_component());
+    assert_eq!(prices.ema, oracle.ema_price_value() as i64);
+    assert_eq!(prices.ema_conf, oracle.ema_confidence_value());
+    assert_eq!(prices.exponent, oracle.exponent());
+}
+
+#[test]
+fn test_verify_size() {
+    let mut oracle = zeroed();
+    oracle.size = OracleV1::LEN as u32;
+    assert_eq!(oracle.verify_size(OracleV1::LEN), Ok(()));
+    assert_eq!(oracle.verify_size(OracleV1::LEN - 1), Err(ProgramError::InvalidAccountData));
+    oracle.size = 0;
+    assert_eq!(oracle.verify_size(OracleV1::LEN), Err(ProgramError::InvalidAccountData));
+}
+
+#[test]
+fn test_same_feed() {
+    let product = Pubkey::new_unique();
+    let mut a = zeroed();
+    a.product_account_key = product;
+    a.price_type = 1;
+    let mut b = zeroed();
+    b.product_account_key = product;
+    b.price_type = 1;
+    assert!(a.same_feed(&b));
+
+    b.price_type = 2;
+    assert!(!a.same_feed(&b));
+    b.price_type = 1;
+    b.product_account_key = Pubkey::new_unique();
+    assert!(!a.same_feed(&b));
+}
+
+#[test]
+fn test_buffer_is_clean() {
+    let mut oracle = zeroed();
+    assert!(oracle.buffer_is_clean());
+    oracle.buffer[191] = 1;
+    assert!(!oracle.buffer_is_clean());
+    oracle.buffer[191] = 0;
+    oracle.buffer[0] = u128::MAX;
+    assert!(!oracle.buffer_is_clean());
+}
@@ -0,0 +1,402 @@
+//! Oracle state derived from updates by `build_state`, without a runtime
+
+mod common;
+
+use common::zeroed;
+use custom_oracle::{
+    consts::{ATYPE, EMA_PERIOD, EMA_RENORMALIZE_THRESHOLD, MAGIC},
+    error::OracleError,
+    instruction::Update,
+    processor::{build_state, build_wide_state},
+    state::{
+        OracleFlags, OracleState, OracleV1, PriceStatus, MIN_CONFIDENCE_SLOT,
+        REJECT_BELOW_MIN_CONFIDENCE_SLOT, SEQUENCE_SLOT, VALID_SLOT_DELAY_SLOT,
+    },
+};
+use solana_program::{
+    program_error::ProgramError,
+    program_pack::IsInitialized,
+    pubkey::Pubkey,
+};
+
+/// An initialized oracle with no price yet and the given flags.
+fn initialized(flags: OracleFlags) -> OracleV1 {
+    let mut oracle = zeroed();
+    oracle.magic = MAGIC;
+    oracle.acctype = ATYPE;
+    oracle.drv4 = flags.bits() as i32;
+    oracle
+}
+
+fn update(price: i64, confidence: u64) -> Update {
+    Update {
+        price,
+        confidence,
+        status: PriceStatus::Trading.into(),
+    }
+}
+
+#[test]
+fn test_untrusted_update_retains_prior_price() {
+    let key = Pubkey::new_unique();
+    let oracle = initialized(OracleFlags::EMA_ENABLED);
+    let (prior, retained) = build_state(&key, Some(&oracle), &update(1_000_000, 100), 10, 100).unwrap();
+    assert!(!retained);
+
+    // confidence as wide as the price
+    let (oracle, retained) = build_state(&key, Some(&prior), &update(50, 50), 20, 200).unwrap();
+    assert!(retained);
+    assert_eq!(oracle.price_component, 1_000_000);
+    assert_eq!(oracle.confidence_component, 100);
+    assert_eq!(oracle.status, u32::from(PriceStatus::Unknown));
+    assert_eq!(oracle.publish_slot, prior.publish_slot);
+    assert_eq!(oracle.timestamp, prior.timestamp);
+    assert_eq!(oracle.last_slot, prior.last_slot);
+    assert_eq!(oracle.valid_slot, prior.valid_slot);
+    assert_eq!(oracle.ema_price_value, prior.ema_price_value);
+    assert_eq!(oracle.ema_price_numerator, prior.ema_price_numerator);
+    assert_eq!(oracle.ema_price_denominator, prior.ema_price_denominator);
+    assert_eq!(oracle.ema_confidence_value, prior.ema_confidence_value);
+    assert_eq!(oracle.history_cursor(), prior.history_cursor());
+    assert_eq!(oracle.recent_prices(), prior.recent_prices());
+    assert_eq!(oracle.sequence(), prior.sequence() + 1);
+    assert_eq!(oracle.update_count(), prior.update_count() + 1);
+
+    // a trusted update afterwards resumes from the retained state
+    let (oracle, retained) = build_state(&key, Some(&oracle), &update(1_100_000, 100), 30, 300).unwrap();
+    assert!(!retained);
+    assert_eq!(oracle.price_component, 1_100_000);
+    assert_eq!(oracle.publish_slot, 30);
+    assert_eq!(oracle.timestamp, 300);
+    assert_eq!(oracle.history_cursor(), prior.history_cursor() + 1);
+}
+
+#[test]
+fn test_untrusted_first_update_is_stored_as_unknown() {
+    let key = Pubkey::new_unique();
+    let oracle = initialized(OracleFlags::empty());
+    let (oracle, retained) = build_state(&key, Some(&oracle), &update(50, 60), 10, 100).unwrap();
+    assert!(!retained);
+    assert_eq!(oracle.price_component, 50);
+    assert_eq!(oracle.status, u32::from(PriceStatus::Unknown));
+    assert_eq!(oracle.publish_slot, 10);
+    assert_eq!(oracle.history_cursor(), 1);
+}
+
+#[test]
+fn test_wide_price_beyond_i64_is_trusted_on_its_full_magnitude() {
+    let key = Pubkey::new_unique();
+    let oracle = initialized(OracleFlags::empty());
+    let (prior, _) = build_state(&key, Some(&oracle), &update(1_000_000, 100), 10, 100).unwrap();
+
+    // wider than the saturated V1 price, but narrower than the full one
+    for &price in [i64::MAX as i128 * 4, i64::MIN as i128 * 4].iter() {
+        let (oracle, retained) =
+            build_wide_state(&key, &prior, price, u64::MAX, PriceStatus::Trading.into(), 20, 200).unwrap();
+        assert!(!retained, "{}", price);
+        assert_eq!(oracle.price_component, price.clamp(i64::MIN as i128 + 1, i64::MAX as i128) as i64);
+        assert_eq!(oracle.status, u32::from(PriceStatus::Trading));
+        assert_eq!(oracle.publish_slot, 20);
+    }
+
+    let (oracle, retained) =
+        build_wide_state(&key, &prior, u64::MAX as i128, u64::MAX, PriceStatus::Trading.into(), 20, 200).unwrap();
+    assert!(retained);
+    assert_eq!(oracle.price_component, 1_000_000);
+    assert_eq!(oracle.publish_slot, 10);
+}
+
+/// Applies a trusted update at `price` in `slot`, using the slot as timestamp.
+fn apply(oracle: &OracleV1, price: i64, slot: u64) -> OracleV1 {
+    let key = oracle.product_account_key;
+    build_state(&key, Some(oracle), &update(price, 1), slot, slot as i64).unwrap().0
+}
+
+#[test]
+fn test_ema_moves_in_proportion_to_slot_gap() {
+    let price = 1_000_000_000;
+    let jump = 2_000_000_000;
+    let mut oracle = initialized(OracleFlags::EMA_ENABLED);
+    // a long burst of one-slot updates settles the ema on `price`
+    for slot in 1..=500 {
+        oracle = apply(&oracle, price, slot);
+    }
+    assert_eq!(oracle.ema_price_value, price as u64);
+
+    let mut last_move = 0;
+    for &gap in [1u64, 2, 4, 8, 16].iter() {
+        let moved = apply(&oracle, jump, 500 + gap).ema_price_value as i64 - price;
+        // a sample weighted by `gap` slots against a steady-state weight of
+        // EMA_PERIOD - 1 slots for the decayed history
+        let expected = (jump - price) as f64 * gap as f64 / (EMA_PERIOD - 1 + gap) as f64;
+        assert!((moved as f64 - expected).abs() / expected < 0.001, "{} {} {}", gap, moved, expected);
+        assert!(moved > last_move);
+        last_move = moved;
+    }
+}
+
+#[test]
+fn test_ema_burst_in_one_slot_weighs_as_single_slots() {
+    let mut oracle = initialized(OracleFlags::EMA_ENABLED);
+    for slot in 1..=500 {
+        oracle = apply(&oracle, 1_000, slot);
+    }
+    // repeated updates within one slot each count as a single slot
+    let mut burst = apply(&oracle, 2_000, 501);
+    let single = burst.ema_price_value;
+    burst = apply(&burst, 2_000, 501);
+    assert!(burst.ema_price_value > single);
+    assert!(burst.ema_price_value < apply(&oracle, 2_000, 600).ema_price_value);
+}
+
+#[test]
+fn test_ema_renormalizes_past_threshold() {
+    let mut oracle = initialized(OracleFlags::EMA_ENABLED);
+    oracle = apply(&oracle, 1_000_000_000, 1);
+    // a gap beyond EMA_MAX_SLOT_DELTA weighs the sample far past the threshold
+    let oracle = apply(&oracle, 3_000_000_000, 1 + (1 << 40));
+    assert!(oracle.ema_price_denominator <= EMA_RENORMALIZE_THRESHOLD);
+    assert!(oracle.ema_price_denominator > 0);
+    assert_eq!(
+        oracle.ema_price_value,
+        oracle.ema_price_numerator / oracle.ema_price_denominator
+    );
+    // the distant sample dominates, without overflowing
+    assert!(oracle.ema_price_value > 2_999_000_000 && oracle.ema_price_value <= 3_000_000_000);
+}
+
+#[test]
+fn test_ema_stays_bounded_over_thousands_of_updates() {
+    let mut oracle = initialized(OracleFlags::EMA_ENABLED);
+    let mut slot = 0u64;
+    for i in 0..5_000u64 {
+        // prices near i64::MAX with gaps from one slot to far past the clamp
+        slot += match i % 7 {
+            0 => 1 << 36,
+            1 | 2 => 1,
+            3 => 400,
+            _ => 1 + i % 50,
+        };
+        let price = i64::MAX - (i % 13) as i64 * 1_000_000_000;
+        oracle = apply(&oracle, price, slot);
+        assert!(oracle.ema_price_denominator <= EMA_RENORMALIZE_THRESHOLD, "{}", i);
+        assert!(oracle.ema_confidence_denominator <= EMA_RENORMALIZE_THRESHOLD, "{}", i);
+        let ema = oracle.ema_price_value as i64;
+        assert!(ema >= i64::MAX - 12_000_000_000, "{} {}", i, ema);
+    }
+
+    // a steady run afterwards converges on the steady price
+    for _ in 0..1_000 {
+        slot += 1;
+        oracle = apply(&oracle, 5_000_000, slot);
+    }
+    assert_eq!(oracle.ema_price_value, 5_000_000);
+    assert_eq!(oracle.ema_confidence_value, 1);
+}
+
+#[test]
+fn test_confidence_below_floor_is_clamped() {
+    let key = Pubkey::new_unique();
+    let mut oracle = initialized(OracleFlags::empty());
+    oracle.buffer[MIN_CONFIDENCE_SLOT] = 500;
+    assert_eq!(oracle.min_confidence(), 500);
+    assert!(!oracle.reject_below_min_confidence());
+
+    for &confidence in [0, 1, 499].iter() {
+        let (state, _) = build_state(&key, Some(&oracle), &update(1_000_000, confidence), 10, 100).unwrap();
+        assert_eq!(state.confidence_component, 500);
+    }
+    let (state, _) = build_state(&key, Some(&oracle), &update(1_000_000, 501), 10, 100).unwrap();
+    assert_eq!(state.confidence_component, 501);
+}
+
+#[test]
+fn test_confidence_below_floor_is_rejected_in_reject_mode() {
+    let key = Pubkey::new_unique();
+    let mut oracle = initialized(OracleFlags::empty());
+    oracle.buffer[MIN_CONFIDENCE_SLOT] = 500;
+    oracle.buffer[REJECT_BELOW_MIN_CONFIDENCE_SLOT] = 1;
+    assert!(oracle.reject_below_min_confidence());
+
+    for &confidence in [0, 499].iter() {
+        assert_eq!(
+            build_state(&key, Some(&oracle), &update(1_000_000, confidence), 10, 100),
+            Err(OracleError::ConfidenceBelowMinimum.into())
+        );
+    }
+    let (state, _) = build_state(&key, Some(&oracle), &update(1_000_000, 500), 10, 100).unwrap();
+    assert_eq!(state.confidence_component, 500);
+}
+
+#[test]
+fn test_status_is_downgraded_when_confidence_reaches_price() {
+    let key = Pubkey::new_unique();
+    let oracle = initialized(OracleFlags::empty());
+
+    // a tight interval keeps the requested status
+    let (state, _) = build_state(&key, Some(&oracle), &update(1_000_000, 999_999), 10, 100).unwrap();
+    assert_eq!(state.status, u32::from(PriceStatus::Trading));
+    let (state, _) = build_state(&key, Some(&oracle), &update(-1_000_000, 999_999), 10, 100).unwrap();
+    assert_eq!(state.status, u32::from(PriceStatus::Trading));
+
+    // an interval as wide as the price's magnitude is downgraded, either sign
+    for &(price, confidence) in [(1_000_000, 1_000_000), (-1_000_000, 1_000_000), (1_000_000, u64::MAX)].iter() {
+        let (state, _) = build_state(&key, Some(&oracle), &update(price, confidence), 10, 100).unwrap();
+        assert_eq!(state.status, u32::from(PriceStatus::Unknown));
+    }
+}
+
+#[test]
+fn test_timestamp_never_goes_backwards() {
+    let key = Pubkey::new_unique();
+    let oracle = initialized(OracleFlags::empty());
+
+    // the first write accepts any timestamp
+    let (prior, _) = build_state(&key, None, &update(1_000_000, 100), 10, -5).unwrap();
+    assert_eq!(prior.timestamp, -5);
+    let (prior, _) = build_state(&key, Some(&oracle), &update(1_000_000, 100), 10, 100).unwrap();
+
+    assert_eq!(
+        build_state(&key, Some(&prior), &update(1_000_000, 100), 11, 99),
+        Err(OracleError::TimestampOutOfRange.into())
+    );
+    let (state, _) = build_state(&key, Some(&prior), &update(1_000_000, 100), 11, 100).unwrap();
+    assert_eq!(state.timestamp, 100);
+    let (state, _) = build_state(&key, Some(&prior), &update(1_000_000, 100), 11, 101).unwrap();
+    assert_eq!(state.timestamp, 101);
+}
+
+#[test]
+fn test_valid_slot_trails_publish_slot_by_delay() {
+    let key = Pubkey::new_unique();
+    for &delay in [0, 1, 25].iter() {
+        let mut oracle = initialized(OracleFlags::empty());
+        oracle.buffer[VALID_SLOT_DELAY_SLOT] = delay as u128;
+        assert_eq!(oracle.valid_slot_delay(), delay);
+
+        let (state, _) = build_state(&key, Some(&oracle), &update(1_000_000, 100), 10, 100).unwrap();
+        assert_eq!(state.publish_slot, 10);
+        assert_eq!(state.last_slot, 10);
+        assert_eq!(state.valid_slot, 10 + delay);
+    }
+
+    // saturates rather than wrapping
+    let mut oracle = initialized(OracleFlags::empty());
+    oracle.buffer[VALID_SLOT_DELAY_SLOT] = 25;
+    let (state, _) = build_state(&key, Some(&oracle), &update(1_000_000, 100), u64::MAX - 1, 100).unwrap();
+    assert_eq!(state.valid_slot, u64::MAX);
+}
+
+#[test]
+fn test_sequence_overflow_fails_instead_of_wrapping() {
+    let key = Pubkey::new_unique();
+    let mut oracle = initialized(OracleFlags::empty());
+    oracle.buffer[SEQUENCE_SLOT] = (u64::MAX - 1) as u128;
+    let (oracle, _) = build_state(&key, Some(&oracle), &update(1_000_000, 100), 10, 100).unwrap();
+    assert_eq!(oracle.sequence(), u64::MAX);
+
+    assert_eq!(
+        build_state(&key, Some(&oracle), &update(1_000_000, 100), 11, 100),
+        Err(OracleError::ArithmeticError.into())
+    );
+    // an untrusted update still advances the sequence, so it fails too
+    assert_eq!(
+        build_state(&key, Some(&oracle), &update(50, 50), 11, 100),
+        Err(OracleError::ArithmeticError.into())
+    );
+}
+
+#[test]
+fn test_other_account_type_is_rejected() {
+    let key = Pubkey::new_unique();
+    let mut oracle = initialized(OracleFlags::empty());
+    // a product account, say
+    oracle.acctype = ATYPE - 1;
+    assert_eq!(
+        build_state(&key, Some(&oracle), &update(1_000_000, 100), 10, 100),
+        Err(ProgramError::InvalidAccountData)
+    );
+}
+
+#[test]
+fn test_sentinel_prices_are_rejected() {
+    let key = Pubkey::new_unique();
+    let oracle = initialized(OracleFlags::empty());
+    assert_eq!(
+        build_state(&key, Some(&oracle), &update(i64::MIN, 100), 10, 100),
+        Err(OracleError::InvalidPrice.into())
+    );
+    let (state, _) = build_state(&key, Some(&oracle), &update(i64::MIN + 1, 100), 10, 100).unwrap();
+    assert_eq!(state.price_component, i64::MIN + 1);
+
+    // zero is only a sentinel when the oracle opts in
+    let (state, _) = build_state(&key, Some(&oracle), &update(0, 0), 10, 100).unwrap();
+    assert_eq!(state.price_component, 0);
+    let oracle = initialized(OracleFlags::REJECT_ZERO_PRICE);
+    assert_eq!(
+        build_state(&key, Some(&oracle), &update(0, 0), 10, 100),
+        Err(OracleError::InvalidPrice.into())
+    );
+    assert!(build_state(&key, Some(&oracle), &update(1_000_000, 100), 10, 100).is_ok());
+}
+
+#[test]
+fn test_first_write_then_subsequent_write() {
+    let key = Pubkey::new_unique();
+    let (first, retained) = build_state(&key, None, &update(1_000_000, 100), 10, 100).unwrap();
+    assert!(!retained);
+    assert_eq!(first.magic, MAGIC);
+    assert_eq!(first.acctype, ATYPE);
+    assert_eq!(first.product_account_key, key);
+    assert_eq!(first.price_component, 1_000_000);
+    assert_eq!(first.confidence_component, 100);
+    assert_eq!(first.status, u32::from(PriceStatus::Trading));
+    assert_eq!((first.last_slot, first.valid_slot, first.publish_slot), (10, 10, 10));
+    assert_eq!(first.timestamp, 100);
+    assert_eq!(first.previous_price_component, 0);
+    assert_eq!(first.sequence(), 1);
+    assert!(first.has_price());
+
+    // the product key sticks to the first writer's
+    let (second, _) =
+        build_state(&Pubkey::new_unique(), Some(&first), &update(1_100_000, 200), 20, 200).unwrap();
+    assert_eq!(second.product_account_key, key);
+    assert_eq!(second.price_component, 1_100_000);
+    assert_eq!((second.last_slot, second.valid_slot, second.publish_slot), (20, 20, 20));
+    assert_eq!(second.timestamp, 200);
+    assert_eq!(second.previous_slot, 10);
+    assert_eq!(second.previous_price_component, 1_000_000);
+    assert_eq!(second.previous_confidence_component, 100);
+    assert_eq!(second.previous_timestamp, 100);
+    assert_eq!(second.sequence(), 2);
+}
+
+#[test]
+fn test_previous_bundle_after_two_updates() {
+    let key = Pubkey::new_unique();
+    let oracle = initialized(OracleFlags::empty());
+    let (oracle, _) = build_state(&key, Some(&oracle), &update(1_000_000, 100), 10, 100).unwrap();
+    let (oracle, _) = build_state(&key, Some(&oracle), &update(1_100_000, 200), 20, 200).unwrap();
+
+    let previous = oracle.previous();
+    assert_eq!(previous.slot, oracle.previous_slot());
+    assert_eq!(previous.price, oracle.previous_price_component());
+    assert_eq!(previous.confidence, oracle.previous_confidence_component());
+    assert_eq!(previous.timestamp, oracle.previous_timestamp());
+    assert_eq!((previous.slot, previous.price, previous.confidence, previous.timestamp), (10, 1_000_000, 100, 100));
+}
+
+#[test]
+fn test_first_update_marks_has_price() {
+    let key = Pubkey::new_unique();
+    let mut oracle = initialized(OracleFlags::EMA_ENABLED);
+    oracle.price_component = 1_000_000;
+    assert!(oracle.is_initialized());
+    assert!(!oracle.has_price());
+
+    let (priced, _) = build_state(&key, Some(&oracle), &update(1_000_000, 100), 10, 100).unwrap();
+    assert!(priced.has_price());
+    assert!(priced.flags().contains(OracleFlags::EMA_ENABLED));
+    let (priced, _) = build_state(&key, Some(&priced), &update(1_100_000, 100), 20, 200).unwrap();
+    assert!(priced.has_price());
+}
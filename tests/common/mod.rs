@@ -0,0 +1,9 @@
+//! Fixtures shared by the integration tests
+
+use custom_oracle::state::OracleV1;
+use solana_program::program_pack::Pack;
+
+/// An all-zero oracle, as a freshly allocated account would unpack.
+pub fn zeroed() -> OracleV1 {
+    OracleV1::unpack_unchecked(&[0; OracleV1::LEN]).unwrap()
+}
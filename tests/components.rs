@@ -0,0 +1,132 @@
+//! Read-side aggregation of the published component prices
+
+mod common;
+
+use common::zeroed;
+use custom_oracle::{
+    consts::{ATYPE, MAGIC, NUM_COMPONENT},
+    state::{OracleState, OracleV1, AGGREGATION_TRIM_SLOT, MAX_COMPONENTS},
+};
+use solana_program::{program_pack::Pack, pubkey::Pubkey};
+
+/// An initialized oracle with `prices` published as its first components.
+fn with_components(prices: &[i64]) -> OracleV1 {
+    let mut oracle = zeroed();
+    oracle.magic = MAGIC;
+    oracle.acctype = ATYPE;
+    oracle.num_component_prices = NUM_COMPONENT;
+    for (index, &price) in prices.iter().enumerate() {
+        oracle.set_component(index, &Pubkey::new_unique(), price, 1);
+    }
+    oracle
+}
+
+#[test]
+fn test_trim_discards_injected_outlier() {
+    let mut oracle = with_components(&[100, 101, 99, 100, 10_000]);
+    assert_eq!(oracle.trimmed_component_price(), Some(2_080));
+
+    oracle.buffer[AGGREGATION_TRIM_SLOT] = 1;
+    assert_eq!(oracle.aggregation_trim(), 1);
+    assert_eq!(oracle.trimmed_component_price(), Some(100));
+}
+
+#[test]
+fn test_trim_never_discards_every_component() {
+    let mut oracle = with_components(&[100, 200, 300, 400]);
+    oracle.buffer[AGGREGATION_TRIM_SLOT] = 1;
+    assert_eq!(oracle.trimmed_component_price(), Some(250));
+    oracle.buffer[AGGREGATION_TRIM_SLOT] = 2;
+    assert_eq!(oracle.trimmed_component_price(), None);
+    oracle.buffer[AGGREGATION_TRIM_SLOT] = u64::MAX as u128;
+    assert_eq!(oracle.trimmed_component_price(), None);
+    assert_eq!(with_components(&[]).trimmed_component_price(), None);
+}
+
+#[test]
+fn test_slots_beyond_num_component_prices_are_ignored() {
+    let mut oracle = with_components(&[100, 200]);
+    oracle.set_component(NUM_COMPONENT as usize, &Pubkey::new_unique(), 1_000_000, 1);
+    assert_eq!(oracle.active_component_count(), NUM_COMPONENT);
+    assert_eq!(oracle.component_publishers().len(), 2);
+    assert_eq!(oracle.trimmed_component_price(), Some(150));
+
+    oracle.num_component_prices = NUM_COMPONENT + 1;
+    assert_eq!(oracle.component_publishers().len(), 3);
+    assert_eq!(oracle.trimmed_component_price(), Some(333_433));
+}
+
+#[test]
+fn test_active_component_count_is_capped() {
+    let mut oracle = with_components(&[]);
+    oracle.num_component_prices = u32::MAX;
+    assert_eq!(oracle.active_component_count(), MAX_COMPONENTS as u32);
+    oracle.num_component_prices = 0;
+    assert_eq!(oracle.active_component_count(), 0);
+}
+
+#[test]
+fn test_component_publishers_round_trip() {
+    let mut oracle = with_components(&[]);
+    let publishers: Vec<Pubkey> = (0..3).map(|_| Pubkey::new_unique()).collect();
+    for (index, publisher) in publishers.iter().enumerate() {
+        oracle.set_component(index * 2, publisher, -(index as i64) * 100, index as u64 + 7);
+    }
+
+    let oracle = OracleV1::unpack_unchecked(&oracle.to_bytes()).unwrap();
+    assert_eq!(
+        oracle.component_publishers(),
+        vec![(publishers[0], 0), (publishers[1], -100), (publishers[2], -200)]
+    );
+    for (index, publisher) in publishers.iter().enumerate() {
+        assert_eq!(oracle.component_publisher(index * 2), *publisher);
+        assert_eq!(oracle.component(index * 2), (-(index as i64) * 100, index as u64 + 7));
+    }
+    assert_eq!(oracle.component_publisher(1), Pubkey::default());
+}
+
+#[test]
+fn test_components_agree_within_spread() {
+    // 2% between the lowest and highest
+    let clustered = with_components(&[1_000, 1_010, 1_020, 1_005]);
+    assert!(clustered.components_agree(200));
+    assert!(!clustered.components_agree(199));
+
+    let outlier = with_components(&[1_000, 1_010, 1_020, 2_000]);
+    assert!(!outlier.components_agree(200));
+
+    assert!(!with_components(&[]).components_agree(u64::MAX));
+    assert!(!with_components(&[0, 0]).components_agree(u64::MAX));
+}
+
+#[test]
+fn test_scaled_components_apply_exponent() {
+    let mut oracle = with_components(&[12_345, 100, -50]);
+    oracle.num_component_prices = 3;
+    oracle.exponent = -2;
+    let scaled = oracle.scaled_components();
+    assert_eq!(scaled.len(), 3);
+    for (scaled, expected) in scaled.iter().zip([123.45, 1.0, -0.5].iter()) {
+        assert!((scaled - expected).abs() < 1e-9, "{} {}", scaled, expected);
+    }
+
+    oracle.exponent = 2;
+    assert_eq!(oracle.scaled_components(), vec![1_234_500.0, 10_000.0, -5_000.0]);
+}
+
+#[test]
+fn test_geometric_mean_components() {
+    let mut oracle = with_components(&[200, 800, 0]);
+    oracle.num_component_prices = 2;
+    let mean = oracle.geometric_mean_components().unwrap();
+    assert!((mean - 400.0).abs() < 1e-9, "{}", mean);
+    oracle.exponent = -2;
+    let mean = oracle.geometric_mean_components().unwrap();
+    assert!((mean - 4.0).abs() < 1e-9, "{}", mean);
+
+    // a zero component
+    oracle.num_component_prices = 3;
+    assert_eq!(oracle.geometric_mean_components(), None);
+    assert_eq!(with_components(&[100, -100]).geometric_mean_components(), None);
+    assert_eq!(with_components(&[]).geometric_mean_components(), None);
+}
@@ -0,0 +1,68 @@
+//! Compute unit measurements against the BPF build, run with `cargo test-bpf`
+#![cfg(feature = "test-bpf")]
+
+use custom_oracle::{
+    client::estimated_compute_units,
+    instruction::{OracleInstruction, Update},
+    state::{find_config_address, OracleV1, PriceStatus},
+};
+use solana_program::{
+    instruction::{AccountMeta, Instruction},
+    program_pack::Pack,
+};
+use solana_program_test::ProgramTest;
+use solana_sdk::{
+    account::Account,
+    signature::{Keypair, Signer},
+    transaction::Transaction,
+};
+
+#[tokio::test]
+async fn test_update_within_estimated_compute_units() {
+    let oracle = Keypair::new();
+    let mut program_test = ProgramTest::new("custom_oracle", custom_oracle::id(), None);
+    program_test.prefer_bpf(true);
+    program_test.add_account(
+        oracle.pubkey(),
+        Account {
+            lamports: 1_000_000_000,
+            data: vec![0; OracleV1::LEN],
+            owner: custom_oracle::id(),
+            ..Account::default()
+        },
+    );
+    let mut context = program_test.start_with_context().await;
+
+    let instruction = Instruction {
+        program_id: custom_oracle::id(),
+        accounts: vec![
+            AccountMeta::new(oracle.pubkey(), true),
+            AccountMeta::new_readonly(find_config_address(&custom_oracle::id()).0, false),
+        ],
+        data: OracleInstruction::Update(Update {
+            price: 1_000_000,
+            confidence: 100,
+            status: PriceStatus::Trading.into(),
+        })
+        .pack(),
+    };
+    let transaction = Transaction::new_signed_with_payer(
+        &[instruction],
+        Some(&context.payer.pubkey()),
+        &[&context.payer, &oracle],
+        context.last_blockhash,
+    );
+    let simulation = context
+        .banks_client
+        .simulate_transaction(transaction)
+        .await
+        .unwrap();
+    assert!(simulation.result.unwrap().is_ok());
+    let units_consumed = simulation.simulation_details.unwrap().units_consumed;
+    assert!(
+        units_consumed <= estimated_compute_units() as u64,
+        "Update consumed {} compute units, above the estimate of {}",
+        units_consumed,
+        estimated_compute_units(),
+    );
+}
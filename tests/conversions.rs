@@ -0,0 +1,88 @@
+//! Conversions between stored codes and the enums they encode
+
+mod common;
+
+use common::zeroed;
+use custom_oracle::{
+    error::OracleError,
+    instruction::Update,
+    state::{CorporateAction, OracleState, PriceStatus},
+};
+use solana_program::program_error::ProgramError;
+use std::convert::TryFrom;
+
+#[test]
+fn test_price_status_round_trips() {
+    for &status in [
+        PriceStatus::Unknown,
+        PriceStatus::Trading,
+        PriceStatus::Halted,
+        PriceStatus::Auction,
+    ]
+    .iter()
+    {
+        assert_eq!(PriceStatus::try_from(u32::from(status)), Ok(status));
+    }
+    assert_eq!(PriceStatus::try_from(4), Err(OracleError::InvalidStatus));
+    assert_eq!(PriceStatus::try_from(u32::MAX), Err(OracleError::InvalidStatus));
+}
+
+#[test]
+fn test_error_codes_round_trip() {
+    let errors = [
+        OracleError::InvalidInstruction,
+        OracleError::IncorrectSigner,
+        OracleError::InvalidStatus,
+        OracleError::AccountNotWritable,
+        OracleError::ConfidenceBelowMinimum,
+        OracleError::IncorrectAuthority,
+        OracleError::ArithmeticError,
+        OracleError::InvalidPrice,
+        OracleError::AccountFrozen,
+        OracleError::InvalidSignature,
+        OracleError::ProgramPaused,
+        OracleError::TimestampOutOfRange,
+        OracleError::ComponentIndexOutOfRange,
+        OracleError::NotRentExempt,
+        OracleError::InvalidPriceType,
+        OracleError::UntrustedPrice,
+    ];
+    for (code, error) in errors.iter().enumerate() {
+        let code = code as u32;
+        assert_eq!(ProgramError::from(error.clone()), ProgramError::Custom(code));
+        assert_eq!(OracleError::from_code(code).as_ref(), Some(error));
+    }
+    assert_eq!(OracleError::from_code(errors.len() as u32), None);
+}
+
+#[test]
+fn test_corporate_action_codes() {
+    let mut oracle = zeroed();
+    assert!(!oracle.has_pending_corporate_action());
+    assert_eq!(oracle.corporate_action_kind(), CorporateAction::None);
+
+    for &(code, action) in [
+        (1, CorporateAction::Split),
+        (2, CorporateAction::Dividend),
+        (3, CorporateAction::Merger),
+        (4, CorporateAction::Delisting),
+        (99, CorporateAction::Other(99)),
+    ]
+    .iter()
+    {
+        oracle.corporate_action = code;
+        assert!(oracle.has_pending_corporate_action());
+        assert_eq!(oracle.corporate_action_kind(), action);
+    }
+}
+
+#[test]
+fn test_update_new_checks_status() {
+    let update = Update::new(1_000, 10, PriceStatus::Trading.into()).unwrap();
+    assert_eq!(update.price, 1_000);
+    assert_eq!(update.confidence, 10);
+    assert_eq!(update.status, u32::from(PriceStatus::Trading));
+    assert!(Update::new(1_000, 10, PriceStatus::Halted.into()).is_ok());
+
+    assert_eq!(Update::new(1_000, 10, 4), Err(OracleError::InvalidStatus));
+}
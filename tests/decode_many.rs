@@ -0,0 +1,55 @@
+//! `decode_many` decodes each account independently, with the checks of a
+//! single unpack
+
+use custom_oracle::{
+    client::decode_many,
+    instruction::Update,
+    processor::build_state,
+    state::{OracleV1, OracleVersion, PriceStatus},
+};
+use solana_program::{program_error::ProgramError, program_pack::Pack, pubkey::Pubkey};
+
+fn oracle(price: i64) -> OracleV1 {
+    let update = Update {
+        price,
+        confidence: 10,
+        status: PriceStatus::Trading.into(),
+    };
+    build_state(&Pubkey::new_unique(), None, &update, 10, 100).unwrap().0
+}
+
+#[test]
+fn test_decode_many_returns_one_result_per_account() {
+    let first = oracle(1_000);
+    let second = oracle(-2_000);
+    let zeroed = [0; OracleV1::LEN];
+    let mut wrong_magic = first.to_bytes();
+    wrong_magic[0] ^= 1;
+    let first_bytes = first.to_bytes();
+    let second_bytes = second.to_bytes();
+    let datas: [&[u8]; 5] = [
+        &first_bytes,
+        &zeroed,
+        &second_bytes,
+        &wrong_magic,
+        &first_bytes[..100],
+    ];
+
+    let decoded = decode_many(&datas);
+    assert_eq!(decoded.len(), datas.len());
+    assert_eq!(decoded[0], Ok(first));
+    assert_eq!(decoded[1], Err(ProgramError::UninitializedAccount));
+    assert_eq!(decoded[2], Ok(second));
+    assert_eq!(decoded[3], Err(ProgramError::UninitializedAccount));
+    assert!(decoded[4].is_err());
+}
+
+#[test]
+fn test_decode_many_matches_single_unpack() {
+    let oracles: Vec<[u8; OracleV1::LEN]> = (1..=8).map(|price| oracle(price).to_bytes()).collect();
+    let datas: Vec<&[u8]> = oracles.iter().map(|data| &data[..]).collect();
+    for (decoded, data) in decode_many(&datas).into_iter().zip(datas) {
+        assert_eq!(decoded.unwrap(), OracleVersion::unpack_v1(data).unwrap());
+    }
+    assert!(decode_many(&[]).is_empty());
+}
@@ -0,0 +1,127 @@
+//! `verify_ed25519_instruction` accepts only an ed25519 program instruction
+//! carrying the expected pubkey and message
+
+use custom_oracle::{
+    error::OracleError,
+    instruction::{Update, UpdateSigned},
+    processor::verify_ed25519_instruction,
+    state::PriceStatus,
+};
+use solana_program::{
+    ed25519_program,
+    entrypoint::ProgramResult,
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+};
+
+/// Offset of the public key in ed25519 program instruction data, after the
+/// signature count, padding and one offsets record.
+const PUBKEY_OFFSET: usize = 16;
+const SIGNATURE_OFFSET: usize = PUBKEY_OFFSET + 32;
+const MESSAGE_OFFSET: usize = SIGNATURE_OFFSET + 64;
+
+/// An ed25519 program instruction checking one signature by `pubkey` over
+/// `message`, everything carried in the instruction itself. The signature
+/// bytes are left blank: the precompile checks them, not the oracle program.
+fn ed25519_instruction(pubkey: &Pubkey, message: &[u8]) -> Instruction {
+    // u16::MAX instruction indexes point at this instruction
+    let mut data = vec![1, 0];
+    for value in [
+        SIGNATURE_OFFSET as u16,
+        u16::MAX,
+        PUBKEY_OFFSET as u16,
+        u16::MAX,
+        MESSAGE_OFFSET as u16,
+        message.len() as u16,
+        u16::MAX,
+    ]
+    .iter()
+    {
+        data.extend_from_slice(&value.to_le_bytes());
+    }
+    data.extend_from_slice(pubkey.as_ref());
+    data.extend_from_slice(&[0; 64]);
+    data.extend_from_slice(message);
+    Instruction {
+        program_id: ed25519_program::id(),
+        accounts: vec![],
+        data,
+    }
+}
+
+fn message() -> Vec<u8> {
+    UpdateSigned {
+        update: Update {
+            price: 1_000_000,
+            confidence: 100,
+            status: PriceStatus::Trading.into(),
+        },
+        slot: 42,
+    }
+    .message(&Pubkey::new_unique())
+}
+
+fn invalid_signature() -> ProgramResult {
+    Err(OracleError::InvalidSignature.into())
+}
+
+#[test]
+fn test_valid_instruction_is_accepted() {
+    let authority = Pubkey::new_unique();
+    let message = message();
+    let ix = ed25519_instruction(&authority, &message);
+    assert_eq!(verify_ed25519_instruction(&ix, &authority, &message), Ok(()));
+}
+
+#[test]
+fn test_tampered_message_is_rejected() {
+    let authority = Pubkey::new_unique();
+    let message = message();
+    let mut ix = ed25519_instruction(&authority, &message);
+    *ix.data.last_mut().unwrap() ^= 1;
+    assert_eq!(verify_ed25519_instruction(&ix, &authority, &message), invalid_signature());
+
+    // a signature over a prefix of the expected message
+    let ix = ed25519_instruction(&authority, &message[..message.len() - 1]);
+    assert_eq!(verify_ed25519_instruction(&ix, &authority, &message), invalid_signature());
+}
+
+#[test]
+fn test_tampered_pubkey_is_rejected() {
+    let authority = Pubkey::new_unique();
+    let message = message();
+    let ix = ed25519_instruction(&Pubkey::new_unique(), &message);
+    assert_eq!(verify_ed25519_instruction(&ix, &authority, &message), invalid_signature());
+
+    let mut ix = ed25519_instruction(&authority, &message);
+    ix.data[PUBKEY_OFFSET] ^= 1;
+    assert_eq!(verify_ed25519_instruction(&ix, &authority, &message), invalid_signature());
+}
+
+#[test]
+fn test_malformed_instruction_is_rejected() {
+    let authority = Pubkey::new_unique();
+    let message = message();
+
+    let mut ix = ed25519_instruction(&authority, &message);
+    ix.program_id = Pubkey::new_unique();
+    assert_eq!(verify_ed25519_instruction(&ix, &authority, &message), invalid_signature());
+
+    let mut ix = ed25519_instruction(&authority, &message);
+    ix.accounts.push(AccountMeta::new_readonly(Pubkey::new_unique(), false));
+    assert_eq!(verify_ed25519_instruction(&ix, &authority, &message), invalid_signature());
+
+    // two signatures
+    let mut ix = ed25519_instruction(&authority, &message);
+    ix.data[0] = 2;
+    assert_eq!(verify_ed25519_instruction(&ix, &authority, &message), invalid_signature());
+
+    // pubkey read from another instruction
+    let mut ix = ed25519_instruction(&authority, &message);
+    ix.data[8..10].copy_from_slice(&0u16.to_le_bytes());
+    assert_eq!(verify_ed25519_instruction(&ix, &authority, &message), invalid_signature());
+
+    let mut ix = ed25519_instruction(&authority, &message);
+    ix.data.truncate(10);
+    assert_eq!(verify_ed25519_instruction(&ix, &authority, &message), invalid_signature());
+}
@@ -0,0 +1,100 @@
+//! Age and staleness of a price relative to a given clock
+
+mod common;
+
+use common::zeroed;
+use custom_oracle::state::{OracleHealth, OracleState, OracleV1, PriceStatus, MAX_STALENESS_SLOT};
+
+/// A zeroed oracle published at `publish_slot` and valid from `valid_slot`.
+fn oracle(publish_slot: u64, valid_slot: u64) -> OracleV1 {
+    let mut oracle = zeroed();
+    oracle.publish_slot = publish_slot;
+    oracle.valid_slot = valid_slot;
+    oracle
+}
+
+#[test]
+fn test_validity_duration_counts_from_valid_slot() {
+    let oracle = oracle(100, 103);
+    assert_eq!(oracle.validity_duration(110), 7);
+    assert!(oracle.is_fresh(110, 10));
+    assert!(!oracle.is_fresh(110, 9));
+    // not yet valid
+    assert_eq!(oracle.validity_duration(101), 0);
+}
+
+#[test]
+fn test_is_fresh() {
+    let oracle = oracle(100, 100);
+    assert!(oracle.is_fresh(100, 0));
+    assert!(oracle.is_fresh(125, 25));
+    assert!(!oracle.is_fresh(126, 25));
+    // a publish slot ahead of the clock counts as fresh
+    assert!(oracle.is_fresh(50, 0));
+}
+
+#[test]
+fn test_is_expired_past_max_staleness() {
+    let mut oracle = oracle(100, 100);
+    // no maximum configured
+    assert!(!oracle.is_expired(u64::MAX));
+
+    oracle.buffer[MAX_STALENESS_SLOT] = 25;
+    assert_eq!(oracle.max_staleness(), 25);
+    assert!(!oracle.is_expired(125));
+    assert!(oracle.is_expired(126));
+}
+
+#[test]
+fn test_scaled_band_and_status() {
+    let mut oracle = oracle(100, 100);
+    oracle.price_component = 1_000;
+    oracle.confidence_component = 10;
+    oracle.status = PriceStatus::Trading.into();
+    assert_eq!(oracle.scaled_band_and_status(110, 10), Some((990.0, 1_000.0, 1_010.0)));
+
+    // stale
+    assert_eq!(oracle.scaled_band_and_status(111, 10), None);
+
+    oracle.status = PriceStatus::Halted.into();
+    assert_eq!(oracle.scaled_band_and_status(110, 10), None);
+}
+
+#[test]
+fn test_confidence_with_staleness() {
+    let mut oracle = oracle(100, 100);
+    oracle.confidence_component = 50;
+    assert_eq!(oracle.confidence_with_staleness(100, 5), 50);
+    assert_eq!(oracle.confidence_with_staleness(110, 5), 100);
+    // not yet published
+    assert_eq!(oracle.confidence_with_staleness(90, 5), 50);
+    // very stale
+    assert_eq!(oracle.confidence_with_staleness(u64::MAX, 5), u64::MAX);
+}
+
+#[test]
+fn test_health_fields() {
+    let mut oracle = oracle(100, 100);
+    oracle.timestamp = 1_650_000_000;
+    oracle.status = PriceStatus::Trading.into();
+    oracle.buffer[MAX_STALENESS_SLOT] = 25;
+    assert_eq!(
+        oracle.health(120, 1_650_000_008),
+        OracleHealth {
+            age_slots: 20,
+            age_seconds: 8,
+            status: PriceStatus::Trading,
+            is_fresh: true,
+            is_trading: true,
+        }
+    );
+
+    oracle.status = 99;
+    let health = oracle.health(200, 1_649_999_999);
+    assert_eq!(health.age_slots, 100);
+    // a timestamp ahead of the clock saturates
+    assert_eq!(health.age_seconds, 0);
+    assert_eq!(health.status, PriceStatus::Unknown);
+    assert!(!health.is_fresh);
+    assert!(!health.is_trading);
+}
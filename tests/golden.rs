@@ -0,0 +1,128 @@
+//! Golden-bytes tests locking the packed OracleV1 layout
+
+use custom_oracle::{
+    consts::SIZE,
+    state::{OracleV1, ACCOUNT_DISCRIMINATOR, HEADER_LEN, MIN_CONFIDENCE_SLOT, SCHEMA_HASH},
+};
+use solana_program::{program_pack::Pack, pubkey::Pubkey};
+
+/// Packed bytes of the fields preceding the buffer for [golden_oracle].
+#[rustfmt::skip]
+const GOLDEN_HEADER: [u8; HEADER_LEN] = [
+    0xd4, 0xc3, 0xb2, 0xa1, 0x02, 0x00, 0x00, 0x00, 0x03, 0x00, 0x00, 0x00, 0xf0, 0x0c, 0x00, 0x00,
+    0x01, 0x00, 0x00, 0x00, 0xf8, 0xff, 0xff, 0xff, 0x0a, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00,
+    0x41, 0x42, 0x0f, 0x00, 0x00, 0x00, 0x00, 0x00, 0x42, 0x42, 0x0f, 0x00, 0x00, 0x00, 0x00, 0x00,
+    0x00, 0xf9, 0x02, 0x95, 0x00, 0x00, 0x00, 0x00, 0x00, 0x2e, 0x59, 0x76, 0x11, 0x00, 0x00, 0x00,
+    0x1e, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x40, 0x42, 0x0f, 0x00, 0x00, 0x00, 0x00, 0x00,
+    0x80, 0xc3, 0xc9, 0x01, 0x00, 0x00, 0x00, 0x00, 0x1e, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    0x00, 0x97, 0xf1, 0x62, 0x00, 0x00, 0x00, 0x00, 0x01, 0xfe, 0xfd, 0xff, 0x01, 0x00, 0x00, 0x00,
+    0x01, 0x01, 0x01, 0x01, 0x01, 0x01, 0x01, 0x01, 0x01, 0x01, 0x01, 0x01, 0x01, 0x01, 0x01, 0x01,
+    0x01, 0x01, 0x01, 0x01, 0x01, 0x01, 0x01, 0x01, 0x01, 0x01, 0x01, 0x01, 0x01, 0x01, 0x01, 0x01,
+    0x02, 0x02, 0x02, 0x02, 0x02, 0x02, 0x02, 0x02, 0x02, 0x02, 0x02, 0x02, 0x02, 0x02, 0x02, 0x02,
+    0x02, 0x02, 0x02, 0x02, 0x02, 0x02, 0x02, 0x02, 0x02, 0x02, 0x02, 0x02, 0x02, 0x02, 0x02, 0x02,
+    0x3f, 0x42, 0x0f, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x18, 0x0d, 0x8f, 0x00, 0x00, 0x00, 0x00,
+    0xa0, 0xbb, 0x0d, 0x00, 0x00, 0x00, 0x00, 0x00, 0xff, 0x96, 0xf1, 0x62, 0x00, 0x00, 0x00, 0x00,
+    0x00, 0x07, 0xfd, 0x6a, 0xff, 0xff, 0xff, 0xff, 0x40, 0x42, 0x0f, 0x00, 0x00, 0x00, 0x00, 0x00,
+    0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x40, 0x42, 0x0f, 0x00, 0x00, 0x00, 0x00, 0x00,
+];
+
+/// [SCHEMA_HASH] of the layout locked by [GOLDEN_HEADER].
+#[rustfmt::skip]
+const GOLDEN_SCHEMA_HASH: [u8; 32] = [
+    0xbe, 0x29, 0x4b, 0x07, 0x4e, 0x49, 0x9c, 0x41, 0xaf, 0xd2, 0x05, 0x8d, 0x35, 0xdc, 0xd8, 0xe0,
+    0x8c, 0x5c, 0xbb, 0x11, 0x64, 0x9a, 0x13, 0x29, 0x35, 0x70, 0x77, 0xb4, 0x6a, 0x6e, 0xd8, 0x64,
+];
+
+/// Minimum confidence stored in the buffer of [golden_oracle].
+const GOLDEN_MIN_CONFIDENCE: u128 = 500;
+
+/// An oracle with every field set to a distinct value, so a field packed at the
+/// wrong offset shows up as a byte mismatch.
+fn golden_oracle() -> OracleV1 {
+    let mut buffer = [0u128; 192];
+    buffer[MIN_CONFIDENCE_SLOT] = GOLDEN_MIN_CONFIDENCE;
+    OracleV1 {
+        magic: 0xa1b2c3d4,
+        version: 2,
+        acctype: 3,
+        size: 3312,
+        price_type: 1,
+        exponent: -8,
+        num_component_prices: 10,
+        num_quoters: 1,
+        last_slot: 1_000_001,
+        valid_slot: 1_000_002,
+        ema_price_value: 2_500_000_000,
+        ema_price_numerator: 75_000_000_000,
+        ema_price_denominator: 30,
+        ema_confidence_value: 1_000_000,
+        ema_confidence_numerator: 30_000_000,
+        ema_confidence_denominator: 30,
+        timestamp: 1_660_000_000,
+        min_publishers: 1,
+        drv2: -2,
+        drv3: -3,
+        drv4: 1,
+        product_account_key: Pubkey::new_from_array([1; 32]),
+        next_price_account_key: Pubkey::new_from_array([2; 32]),
+        previous_slot: 999_999,
+        previous_price_component: 2_400_000_000,
+        previous_confidence_component: 900_000,
+        previous_timestamp: 1_659_999_999,
+        price_component: -2_500_000_000,
+        confidence_component: 1_000_000,
+        status: 1,
+        corporate_action: 0,
+        publish_slot: 1_000_000,
+        buffer,
+    }
+}
+
+fn golden_bytes() -> Vec<u8> {
+    let mut bytes = vec![0; OracleV1::LEN];
+    bytes[..HEADER_LEN].copy_from_slice(&GOLDEN_HEADER);
+    let min_confidence_offset = HEADER_LEN + MIN_CONFIDENCE_SLOT * 16;
+    bytes[min_confidence_offset..min_confidence_offset + 16]
+        .copy_from_slice(&GOLDEN_MIN_CONFIDENCE.to_le_bytes());
+    bytes
+}
+
+#[test]
+fn test_pack_matches_golden_bytes() {
+    let mut packed = vec![0; OracleV1::LEN];
+    golden_oracle().pack_into_slice(&mut packed);
+    assert_eq!(packed[..HEADER_LEN], GOLDEN_HEADER[..]);
+    assert_eq!(packed, golden_bytes());
+}
+
+#[test]
+fn test_unpack_golden_bytes() {
+    let unpacked = OracleV1::unpack_from_slice(&golden_bytes()).unwrap();
+    assert_eq!(unpacked, golden_oracle());
+}
+
+#[test]
+fn test_schema_hash_is_stable() {
+    assert_eq!(SCHEMA_HASH, GOLDEN_SCHEMA_HASH);
+    assert_eq!(OracleV1::schema_hash(), GOLDEN_SCHEMA_HASH);
+}
+
+#[test]
+fn test_account_discriminator_is_stable() {
+    // first 8 bytes of sha256("account:OracleV1")
+    let expected = [196, 165, 211, 138, 148, 207, 196, 108];
+    assert_eq!(ACCOUNT_DISCRIMINATOR, expected);
+    assert_eq!(OracleV1::discriminator(), expected);
+}
+
+#[test]
+fn test_len_matches_size() {
+    assert_eq!(OracleV1::LEN, SIZE as usize);
+}
+
+#[test]
+fn test_to_bytes_matches_pack_into_slice() {
+    let mut packed = vec![0; OracleV1::LEN];
+    golden_oracle().pack_into_slice(&mut packed);
+    assert_eq!(golden_oracle().to_bytes()[..], packed[..]);
+}
@@ -0,0 +1,28 @@
+//! Account guards shared by the instruction handlers
+
+use custom_oracle::{
+    error::OracleError,
+    processor::{require_owner, require_signer, require_writable},
+};
+use solana_program::{account_info::AccountInfo, program_error::ProgramError, pubkey::Pubkey};
+
+#[test]
+fn test_account_guards() {
+    let key = Pubkey::new_unique();
+    let owner = custom_oracle::id();
+    let mut lamports = 0;
+    let mut data = [];
+    let mut info = AccountInfo::new(&key, true, true, &mut lamports, &mut data, &owner, false, 0);
+    assert_eq!(require_signer(&info), Ok(()));
+    assert_eq!(require_writable(&info), Ok(()));
+    assert_eq!(require_owner(&info, &custom_oracle::id()), Ok(()));
+
+    assert_eq!(
+        require_owner(&info, &Pubkey::new_unique()),
+        Err(ProgramError::IncorrectProgramId)
+    );
+    info.is_signer = false;
+    assert_eq!(require_signer(&info), Err(OracleError::IncorrectSigner.into()));
+    info.is_writable = false;
+    assert_eq!(require_writable(&info), Err(OracleError::AccountNotWritable.into()));
+}
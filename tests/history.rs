@@ -0,0 +1,32 @@
+//! Price history ring buffer reads after the buffer wraps around
+
+mod common;
+
+use common::zeroed;
+use custom_oracle::state::{OracleState, HISTORY_LEN};
+
+#[test]
+fn test_history_iter_is_in_slot_order_after_wraparound() {
+    let mut oracle = zeroed();
+    assert_eq!(oracle.history_iter().count(), 0);
+
+    let samples = HISTORY_LEN as u64 + 5;
+    for slot in 1..=samples {
+        oracle.push_history(slot as i64 * 10, 1, slot);
+    }
+    assert_eq!(oracle.history_cursor(), samples);
+
+    let history: Vec<(i64, u64)> = oracle.history_iter().collect();
+    let expected: Vec<(i64, u64)> = (samples - HISTORY_LEN as u64 + 1..=samples)
+        .map(|slot| (slot as i64 * 10, slot))
+        .collect();
+    assert_eq!(history, expected);
+
+    // newest first is the same samples reversed
+    let recent: Vec<(i64, u64)> = oracle
+        .recent_prices()
+        .into_iter()
+        .map(|(price, _, slot)| (price, slot))
+        .collect();
+    assert_eq!(recent, expected.into_iter().rev().collect::<Vec<_>>());
+}
@@ -0,0 +1,48 @@
+//! Checked arithmetic helpers fail cleanly instead of panicking
+
+use custom_oracle::{
+    error::OracleError,
+    math::{apply_exponent, checked_add, checked_div, checked_mul, checked_mul_div, checked_sub},
+};
+
+#[test]
+fn test_checked_helpers_pass_through_results() {
+    assert_eq!(checked_add(2, 3), Ok(5));
+    assert_eq!(checked_sub(2, 3), Ok(-1));
+    assert_eq!(checked_mul(-2, 3), Ok(-6));
+    assert_eq!(checked_div(7, 2), Ok(3));
+    assert_eq!(checked_mul_div(u64::MAX as u128, 4, 2), Ok(u64::MAX as u128 * 2));
+}
+
+#[test]
+fn test_checked_helpers_fail_on_overflow() {
+    assert_eq!(checked_add(i128::MAX, 1), Err(OracleError::ArithmeticError));
+    assert_eq!(checked_sub(i128::MIN, 1), Err(OracleError::ArithmeticError));
+    assert_eq!(checked_mul(i128::MAX, 2), Err(OracleError::ArithmeticError));
+    assert_eq!(checked_div(i128::MIN, -1), Err(OracleError::ArithmeticError));
+    assert_eq!(checked_mul_div(u128::MAX, 2, 2), Err(OracleError::ArithmeticError));
+}
+
+#[test]
+fn test_checked_helpers_fail_on_division_by_zero() {
+    assert_eq!(checked_div(1, 0), Err(OracleError::ArithmeticError));
+    assert_eq!(checked_mul_div(1, 1, 0), Err(OracleError::ArithmeticError));
+}
+
+#[test]
+fn test_apply_exponent() {
+    assert_eq!(apply_exponent(123, 0), Some(123));
+    assert_eq!(apply_exponent(123, 2), Some(12_300));
+    assert_eq!(apply_exponent(-12_345, -2), Some(-123));
+    assert_eq!(apply_exponent(0, -30), Some(0));
+}
+
+#[test]
+fn test_apply_exponent_at_extremes() {
+    // overflow
+    assert_eq!(apply_exponent(i64::MAX as i128, 30), None);
+    assert_eq!(apply_exponent(1, 39), None);
+    assert_eq!(apply_exponent(1, i32::MIN), None);
+    // a nonzero value scaled down to nothing
+    assert_eq!(apply_exponent(i64::MAX as i128, -30), None);
+}
@@ -0,0 +1,92 @@
+//! Every instruction fails with `NotEnoughAccountKeys` when given no accounts
+
+use custom_oracle::{
+    instruction::{
+        AddPublisher, Initialize, OracleInstruction, PublishComponent, Update,
+        UpdateBatchSameAccount, UpdateConfidence, UpdatePctConfidence, UpdateQuote, UpdateSigned,
+        UpdateWide,
+    },
+    processor::Processor,
+    state::OracleFlags,
+};
+use solana_program::{program_error::ProgramError, pubkey::Pubkey};
+
+fn instructions() -> Vec<OracleInstruction> {
+    let update = Update {
+        price: 1_000_000,
+        confidence: 100,
+        status: 1,
+    };
+    vec![
+        OracleInstruction::Update(update),
+        OracleInstruction::Initialize(Initialize {
+            min_confidence: 0,
+            reject_below_min_confidence: false,
+            flags: OracleFlags::empty(),
+            base_decimals: 0,
+            quote_decimals: 0,
+            aggregation_trim: 0,
+            initial_price: 0,
+            max_staleness_slots: 0,
+            valid_slot_delay: 0,
+            num_quoters: 1,
+        }),
+        OracleInstruction::UpdateWithAuthority(update),
+        OracleInstruction::UpdateBatchSameAccount(UpdateBatchSameAccount {
+            samples: vec![(1, 2, 3)],
+        }),
+        OracleInstruction::PrepareTransfer,
+        OracleInstruction::PublishComponent(PublishComponent {
+            index: 0,
+            price: 1,
+            confidence: 2,
+        }),
+        OracleInstruction::UpdateSigned(UpdateSigned { update, slot: 1 }),
+        OracleInstruction::Pause,
+        OracleInstruction::Unpause,
+        OracleInstruction::UpdatePctConfidence(UpdatePctConfidence {
+            price: 1_000_000,
+            confidence_bps: 10,
+            status: 1,
+        }),
+        OracleInstruction::ClearData,
+        OracleInstruction::AddPublisher(AddPublisher {
+            index: 0,
+            publisher: Pubkey::new_unique(),
+        }),
+        OracleInstruction::RemovePublisher { index: 0 },
+        OracleInstruction::UpdateWide(UpdateWide {
+            price: 1_000_000,
+            confidence: 100,
+            status: 1,
+        }),
+        OracleInstruction::SetMetadata { metadata: [7; 32] },
+        OracleInstruction::ProposeAuthority {
+            new_authority: Pubkey::new_unique(),
+        },
+        OracleInstruction::AcceptAuthority,
+        OracleInstruction::SetPriceType { price_type: 2 },
+        OracleInstruction::UpdateQuote(UpdateQuote {
+            bid: 99,
+            ask: 101,
+            status: 1,
+        }),
+        OracleInstruction::UpdateConfidence(UpdateConfidence {
+            confidence: 100,
+            status: 1,
+        }),
+    ]
+}
+
+#[test]
+fn test_empty_accounts_fail_with_not_enough_account_keys() {
+    let program_id = custom_oracle::id();
+    for instruction in instructions() {
+        assert_eq!(
+            Processor::process(&program_id, &[], &instruction.pack()),
+            Err(ProgramError::NotEnoughAccountKeys),
+            "{:?}",
+            instruction
+        );
+    }
+}
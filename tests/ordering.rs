@@ -0,0 +1,58 @@
+//! `OracleV1` snapshots order by recency: `publish_slot`, then sequence
+
+use custom_oracle::{
+    instruction::Update,
+    processor::build_state,
+    state::{OracleState, OracleV1, PriceStatus},
+};
+use solana_program::pubkey::Pubkey;
+use std::cmp::Ordering;
+
+/// Successive snapshots of one oracle, updated at each of `slots`.
+fn snapshots(slots: &[u64]) -> Vec<OracleV1> {
+    let key = Pubkey::new_unique();
+    let mut snapshots: Vec<OracleV1> = Vec::new();
+    for (i, &slot) in slots.iter().enumerate() {
+        let update = Update {
+            // falling prices, so a price ordering would come out reversed
+            price: 1_000_000 - i as i64,
+            confidence: 10,
+            status: PriceStatus::Trading.into(),
+        };
+        let (oracle, _) = build_state(&key, snapshots.last(), &update, slot, 100).unwrap();
+        snapshots.push(oracle);
+    }
+    snapshots
+}
+
+#[test]
+fn test_sort_orders_snapshots_by_recency() {
+    // two updates land in slot 20, told apart by their sequence
+    let expected = snapshots(&[10, 20, 20, 35, 40]);
+    let mut sorted = expected.clone();
+    sorted.reverse();
+    sorted.swap(0, 2);
+    sorted.sort();
+    assert_eq!(sorted, expected);
+    assert!(sorted
+        .windows(2)
+        .all(|pair| (pair[0].publish_slot, pair[0].sequence()) < (pair[1].publish_slot, pair[1].sequence())));
+}
+
+#[test]
+fn test_ties_are_consistent_with_eq() {
+    let a = snapshots(&[10]).remove(0);
+    assert_eq!(a.cmp(&a.clone()), Ordering::Equal);
+
+    // same slot and sequence, different price
+    let mut b = a.clone();
+    b.price_component += 1;
+    assert_ne!(a.cmp(&b), Ordering::Equal);
+    assert_eq!(a.cmp(&b), b.cmp(&a).reverse());
+
+    // differing only in the buffer
+    let mut c = a.clone();
+    c.push_history(1, 1, 1);
+    assert_ne!(a.cmp(&c), Ordering::Equal);
+    assert_eq!(a.cmp(&c), c.cmp(&a).reverse());
+}
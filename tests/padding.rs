@@ -0,0 +1,37 @@
+//! Accounts larger than `OracleV1::LEN` keep their trailing bytes
+
+mod common;
+
+use common::zeroed;
+use custom_oracle::state::{OracleV1, OracleVersion};
+use solana_program::program_pack::Pack;
+
+const ACCOUNT_LEN: usize = 4000;
+const PADDING: u8 = 0xaa;
+
+fn oracle() -> OracleV1 {
+    let mut oracle = zeroed();
+    oracle.size = OracleV1::LEN as u32;
+    oracle.price_component = 123_456_789;
+    oracle.confidence_component = 1_000;
+    oracle.buffer[0] = u128::MAX;
+    oracle
+}
+
+#[test]
+fn test_pack_into_oversized_account_leaves_padding() {
+    let mut data = vec![PADDING; ACCOUNT_LEN];
+    OracleVersion::pack(OracleVersion::OracleV1(oracle()), &mut data).unwrap();
+
+    let mut expected = vec![0; OracleV1::LEN];
+    oracle().pack_into_slice(&mut expected);
+    assert_eq!(data[..OracleV1::LEN], expected[..]);
+    assert!(data[OracleV1::LEN..].iter().all(|&byte| byte == PADDING));
+}
+
+#[test]
+fn test_unpack_oversized_account_reads_first_len_bytes() {
+    let mut data = vec![PADDING; ACCOUNT_LEN];
+    oracle().pack_into_slice(&mut data);
+    assert_eq!(OracleV1::unpack_from_slice(&data).unwrap(), oracle());
+}
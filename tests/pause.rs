@@ -0,0 +1,113 @@
+//! `Pause` and `Unpause` toggle the program config PDA, gated on the admin
+
+use custom_oracle::{
+    error::OracleError,
+    instruction::OracleInstruction,
+    processor::Processor,
+    state::{find_config_address, ProgramConfig},
+};
+use solana_program::{
+    account_info::AccountInfo, entrypoint::ProgramResult, program_error::ProgramError,
+    program_pack::Pack, pubkey::Pubkey, system_program,
+};
+
+/// Processes `instruction` against an already created config PDA holding
+/// `config`, signed by `admin` when `admin_signs`.
+fn process(
+    instruction: OracleInstruction,
+    config: &mut [u8; ProgramConfig::LEN],
+    admin: &Pubkey,
+    admin_signs: bool,
+) -> ProgramResult {
+    let config_key = find_config_address(&custom_oracle::id()).0;
+    process_with_config(instruction, &config_key, config, admin, admin_signs)
+}
+
+fn process_with_config(
+    instruction: OracleInstruction,
+    config_key: &Pubkey,
+    config: &mut [u8; ProgramConfig::LEN],
+    admin: &Pubkey,
+    admin_signs: bool,
+) -> ProgramResult {
+    let program_id = custom_oracle::id();
+    let system_program_id = system_program::id();
+    let (mut config_lamports, mut admin_lamports, mut system_lamports) = (1, 1, 1);
+    let (mut admin_data, mut system_data) = ([], []);
+    let accounts = [
+        AccountInfo::new(
+            config_key,
+            false,
+            true,
+            &mut config_lamports,
+            config,
+            &program_id,
+            false,
+            0,
+        ),
+        AccountInfo::new(
+            admin,
+            admin_signs,
+            true,
+            &mut admin_lamports,
+            &mut admin_data,
+            &system_program_id,
+            false,
+            0,
+        ),
+        AccountInfo::new(
+            &system_program_id,
+            false,
+            false,
+            &mut system_lamports,
+            &mut system_data,
+            &system_program_id,
+            true,
+            0,
+        ),
+    ];
+    Processor::process(&program_id, &accounts, &instruction.pack())
+}
+
+fn paused(config: &[u8; ProgramConfig::LEN]) -> bool {
+    ProgramConfig::unpack(config).unwrap().paused
+}
+
+#[test]
+fn test_pause_and_unpause_toggle_config() {
+    let mut config = [0; ProgramConfig::LEN];
+    process(OracleInstruction::Pause, &mut config, &custom_oracle::admin::id(), true).unwrap();
+    assert!(paused(&config));
+    process(OracleInstruction::Unpause, &mut config, &custom_oracle::admin::id(), true).unwrap();
+    assert!(!paused(&config));
+}
+
+#[test]
+fn test_pause_requires_admin() {
+    let mut config = [0; ProgramConfig::LEN];
+    assert_eq!(
+        process(OracleInstruction::Pause, &mut config, &Pubkey::new_unique(), true),
+        Err(OracleError::IncorrectAuthority.into())
+    );
+    assert_eq!(
+        process(OracleInstruction::Pause, &mut config, &custom_oracle::admin::id(), false),
+        Err(OracleError::IncorrectSigner.into())
+    );
+    assert_eq!(config, [0; ProgramConfig::LEN]);
+}
+
+#[test]
+fn test_pause_rejects_wrong_config_account() {
+    let mut config = [0; ProgramConfig::LEN];
+    assert_eq!(
+        process_with_config(
+            OracleInstruction::Pause,
+            &Pubkey::new_unique(),
+            &mut config,
+            &custom_oracle::admin::id(),
+            true,
+        ),
+        Err(ProgramError::InvalidArgument)
+    );
+    assert_eq!(config, [0; ProgramConfig::LEN]);
+}
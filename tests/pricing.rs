@@ -0,0 +1,252 @@
+//! Read-side price helpers on known prices
+
+mod common;
+
+use common::zeroed;
+use custom_oracle::state::{weighted_mid, OracleState, OracleV1, PriceStatus};
+
+/// A zeroed oracle holding `price` at exponent `-8`.
+fn oracle(price: i64) -> OracleV1 {
+    let mut oracle = zeroed();
+    oracle.exponent = -8;
+    oracle.price_component = price;
+    oracle
+}
+
+#[test]
+fn test_within_tolerance() {
+    // 100 bps of 1_000_000 is 10_000
+    assert!(oracle(1_010_000).within_tolerance(1_000_000, 100));
+    assert!(!oracle(1_010_000).within_tolerance(1_000_000, 99));
+    assert!(oracle(990_000).within_tolerance(1_000_000, 100));
+    assert!(!oracle(989_999).within_tolerance(1_000_000, 100));
+    assert!(oracle(-1_010_000).within_tolerance(-1_000_000, 100));
+}
+
+#[test]
+fn test_within_tolerance_of_zero_reference() {
+    assert!(oracle(0).within_tolerance(0, 0));
+    assert!(oracle(0).within_tolerance(0, u64::MAX));
+    assert!(!oracle(1).within_tolerance(0, u64::MAX));
+}
+
+#[test]
+fn test_max_scaled_price() {
+    let mut oracle = oracle(0);
+    assert_eq!(oracle.max_scaled_price(), i64::MAX as f64 * 1e-8);
+    assert_eq!(oracle.min_scaled_price(), i64::MIN as f64 * 1e-8);
+    oracle.exponent = 0;
+    assert_eq!(oracle.max_scaled_price(), i64::MAX as f64);
+    assert_eq!(oracle.min_scaled_price(), i64::MIN as f64);
+    // far below f64's smallest power of ten every price scales to zero
+    oracle.exponent = -400;
+    assert_eq!(oracle.max_scaled_price(), 0.0);
+    assert_eq!(oracle.min_scaled_price(), 0.0);
+}
+
+#[test]
+fn test_price_q64() {
+    // 1.5 at exponent -8
+    assert_eq!(oracle(150_000_000).price_q64(), 3 << 63);
+    assert_eq!(oracle(100_000_000).price_q64(), 1 << 64);
+    assert_eq!(oracle(0).price_q64(), 0);
+    assert_eq!(oracle(-100_000_000).price_q64(), 0);
+
+    let mut oracle = oracle(2);
+    oracle.exponent = 0;
+    assert_eq!(oracle.price_q64(), 2 << 64);
+}
+
+#[test]
+fn test_ema_spot_divergence_bps() {
+    let mut converged = oracle(1_000_000);
+    converged.ema_price_value = 1_000_000;
+    assert_eq!(converged.ema_spot_divergence_bps(), Some(0));
+
+    let mut diverged = oracle(1_000_000);
+    diverged.ema_price_value = 950_000;
+    assert_eq!(diverged.ema_spot_divergence_bps(), Some(-500));
+    diverged.ema_price_value = 1_100_000;
+    assert_eq!(diverged.ema_spot_divergence_bps(), Some(1_000));
+
+    assert_eq!(oracle(0).ema_spot_divergence_bps(), None);
+}
+
+#[test]
+fn test_confidence_pct() {
+    let mut oracle = oracle(1_000_000);
+    oracle.confidence_component = 10_000;
+    assert_eq!(oracle.confidence_pct(), Some(1.0));
+    oracle.price_component = -1_000_000;
+    assert_eq!(oracle.confidence_pct(), Some(1.0));
+    oracle.price_component = 0;
+    assert_eq!(oracle.confidence_pct(), None);
+}
+
+#[test]
+fn test_scaled_price_for_amount_with_token_decimals() {
+    // SOL at 150 USDC: 1 SOL (9 decimals) is worth 150 USDC (6 decimals)
+    let mut oracle = oracle(150 * 100_000_000);
+    oracle.drv3 = 9;
+    oracle.drv2 = 6;
+    assert_eq!(oracle.base_decimals(), 9);
+    assert_eq!(oracle.quote_decimals(), 6);
+    assert_eq!(oracle.scaled_price_for_amount(1_000_000_000), 150_000_000);
+    assert_eq!(oracle.scaled_price_for_amount(1), 0);
+    assert_eq!(oracle.scaled_price_for_amount(0), 0);
+}
+
+#[test]
+fn test_format_price() {
+    let mut oracle = oracle(123_456_789);
+    assert_eq!(oracle.display_decimals(), 8);
+    assert_eq!(oracle.format_price(), "1.23456789");
+    oracle.price_component = -5;
+    assert_eq!(oracle.format_price(), "-0.00000005");
+
+    oracle.exponent = 2;
+    oracle.price_component = 15;
+    assert_eq!(oracle.display_decimals(), 0);
+    assert_eq!(oracle.format_price(), "1500");
+}
+
+#[test]
+fn test_format_price_at_extreme_exponents() {
+    let mut oracle = oracle(-15);
+    oracle.exponent = 38;
+    assert_eq!(oracle.format_price(), format!("-15{}", "0".repeat(38)));
+    oracle.exponent = -38;
+    assert_eq!(oracle.format_price(), format!("-0.{}15", "0".repeat(36)));
+
+    // past that the exponent is written out instead of the zeros
+    oracle.exponent = i32::MAX;
+    assert_eq!(oracle.format_price(), "-15e2147483647");
+    oracle.exponent = i32::MIN;
+    assert_eq!(oracle.format_price(), "-15e-2147483648");
+    oracle.confidence_component = 3;
+    assert!(oracle.describe().starts_with("price -15e-2147483648 \u{b1} 3e-2147483648,"));
+}
+
+#[test]
+fn test_inverse_price() {
+    // 2.0 inverts to 0.5
+    let two = oracle(200_000_000);
+    assert_eq!(two.inverse_price(), Some(0.5));
+    assert_eq!(two.inverse_price_scaled(-8), Some(50_000_000));
+    assert_eq!(two.inverse_price_scaled(0), Some(0));
+
+    assert_eq!(oracle(0).inverse_price(), None);
+    assert_eq!(oracle(0).inverse_price_scaled(-8), None);
+}
+
+#[test]
+fn test_describe() {
+    let mut oracle = oracle(123_456_789);
+    oracle.confidence_component = 100;
+    oracle.status = PriceStatus::Trading.into();
+    oracle.publish_slot = 42;
+    assert_eq!(
+        oracle.describe(),
+        "price 1.23456789 \u{b1} 0.00000100, status Trading, published at slot 42"
+    );
+
+    oracle.status = 9;
+    assert!(oracle.describe().contains("status Invalid(9)"));
+}
+
+#[test]
+fn test_weighted_mid() {
+    let a = oracle(10_000_000_000);
+    let b = oracle(20_000_000_000);
+    let even = weighted_mid(&a, &b, 5_000).unwrap();
+    assert!((even - 150.0).abs() < 1e-9, "{}", even);
+    let skewed = weighted_mid(&a, &b, 7_000).unwrap();
+    assert!((skewed - 130.0).abs() < 1e-9, "{}", skewed);
+
+    assert_eq!(weighted_mid(&a, &b, 10_000), Some(100.0));
+    assert_eq!(weighted_mid(&a, &b, 10_001), None);
+}
+
+#[test]
+fn test_moved_beyond_confidence() {
+    let mut oracle = oracle(1_000_100);
+    oracle.previous_price_component = 1_000_000;
+    oracle.confidence_component = 100;
+    assert!(!oracle.moved_beyond_confidence());
+
+    oracle.price_component = 999_899;
+    assert!(oracle.moved_beyond_confidence());
+
+    // extreme moves don't overflow
+    oracle.price_component = i64::MAX;
+    oracle.previous_price_component = i64::MIN;
+    assert!(oracle.moved_beyond_confidence());
+}
+
+#[test]
+fn test_price_rational() {
+    let mut oracle = oracle(12_345_000_000);
+    assert_eq!(oracle.price_rational(), (12_345_000_000, 100_000_000));
+
+    oracle.exponent = 2;
+    oracle.price_component = -123;
+    assert_eq!(oracle.price_rational(), (-12_300, 1));
+
+    oracle.exponent = 0;
+    assert_eq!(oracle.price_rational(), (-123, 1));
+}
+
+#[test]
+fn test_price_within_ema_band() {
+    let mut oracle = oracle(1_004_000);
+    oracle.ema_price_value = 1_000_000;
+    assert!(oracle.price_within_ema_band(50));
+
+    // a manipulated spike
+    oracle.price_component = 1_500_000;
+    assert!(!oracle.price_within_ema_band(50));
+    assert!(!oracle.price_within_ema_band(4_999));
+    assert!(oracle.price_within_ema_band(5_000));
+}
+
+#[test]
+fn test_confidence_detail() {
+    let mut oracle = oracle(2_000_000);
+    oracle.confidence_component = 50_000;
+    assert_eq!(oracle.confidence_detail(), (50_000, Some(2.5)));
+    oracle.price_component = 0;
+    assert_eq!(oracle.confidence_detail(), (50_000, None));
+}
+
+#[test]
+fn test_state_commitment() {
+    let mut oracle = oracle(1_000_000);
+    oracle.confidence_component = 100;
+    oracle.status = PriceStatus::Trading.into();
+    oracle.publish_slot = 42;
+    let commitment = oracle.state_commitment();
+    // fields outside the commitment don't change it
+    oracle.ema_price_value = 7;
+    oracle.buffer[0] = 7;
+    assert_eq!(oracle.state_commitment(), commitment);
+
+    oracle.price_component += 1;
+    assert_ne!(oracle.state_commitment(), commitment);
+    oracle.price_component -= 1;
+    assert_eq!(oracle.state_commitment(), commitment);
+    oracle.publish_slot += 1;
+    assert_ne!(oracle.state_commitment(), commitment);
+}
+
+#[test]
+fn test_price_at_exponent() {
+    let oracle = oracle(123_456_789);
+    assert_eq!(oracle.price_at_exponent(-8), Some(123_456_789));
+    // rounds toward zero
+    assert_eq!(oracle.price_at_exponent(-6), Some(1_234_567));
+    assert_eq!(oracle.price_at_exponent(-10), Some(12_345_678_900));
+
+    // overflows an i64, then the exponent difference itself
+    assert_eq!(oracle.price_at_exponent(-19), None);
+    assert_eq!(oracle.price_at_exponent(i32::MAX), None);
+}
@@ -0,0 +1,2161 @@
+//! End-to-end tests driving the program through `solana-program-test`
+
+use custom_oracle::{
+    consts::VERSION_V2,
+    error::OracleError,
+    instruction::{
+        AddPublisher, Initialize, OracleInstruction, PublishComponent, Update,
+        UpdateBatchSameAccount, UpdateConfidence, UpdatePctConfidence, UpdateQuote, UpdateSigned,
+        UpdateWide,
+    },
+    processor::Processor,
+    state::{
+        find_config_address, OracleFlags, OracleState, OracleV1, OracleV2, PriceStatus, PriceType,
+        ProgramConfig, HEADER_LEN, QUOTE_SLOT, SEQUENCE_SLOT, UPDATE_COUNT_SLOT,
+    },
+};
+use solana_program::{
+    ed25519_program,
+    instruction::{AccountMeta, Instruction, InstructionError},
+    program_pack::{IsInitialized, Pack},
+    pubkey::Pubkey,
+    sysvar,
+};
+use solana_program_test::{processor, ProgramTest, ProgramTestContext};
+use solana_sdk::{
+    account::{Account, AccountSharedData},
+    signature::{Keypair, Signer},
+    transaction::{Transaction, TransactionError},
+};
+
+const SLOT: u64 = 100;
+
+/// Starts the program with an empty, program-owned oracle account at `oracle`.
+async fn start(oracle: &Pubkey) -> ProgramTestContext {
+    let mut program_test = ProgramTest::new(
+        "custom_oracle",
+        custom_oracle::id(),
+        processor!(Processor::process),
+    );
+    program_test.add_account(
+        *oracle,
+        Account {
+            lamports: 1_000_000_000,
+            data: vec![0; OracleV1::LEN],
+            owner: custom_oracle::id(),
+            ..Account::default()
+        },
+    );
+    let mut context = program_test.start_with_context().await;
+    context.warp_to_slot(SLOT).unwrap();
+    context
+}
+
+fn update_instruction(oracle: &Pubkey, oracle_is_signer: bool, update: Update) -> Instruction {
+    Instruction {
+        program_id: custom_oracle::id(),
+        accounts: vec![
+            AccountMeta::new(*oracle, oracle_is_signer),
+            AccountMeta::new_readonly(find_config_address(&custom_oracle::id()).0, false),
+        ],
+        data: OracleInstruction::Update(update).pack(),
+    }
+}
+
+/// `instruction` signed by the oracle account itself, followed by the config
+/// PDA checked for a global pause.
+fn signed_oracle_instruction(oracle: &Pubkey, instruction: OracleInstruction) -> Instruction {
+    Instruction {
+        program_id: custom_oracle::id(),
+        accounts: vec![
+            AccountMeta::new(*oracle, true),
+            AccountMeta::new_readonly(find_config_address(&custom_oracle::id()).0, false),
+        ],
+        data: instruction.pack(),
+    }
+}
+
+fn initialize_instruction(oracle: &Pubkey, authority: &Pubkey, initialize: Initialize) -> Instruction {
+    Instruction {
+        program_id: custom_oracle::id(),
+        accounts: vec![
+            AccountMeta::new(*oracle, true),
+            AccountMeta::new_readonly(*authority, false),
+        ],
+        data: OracleInstruction::Initialize(initialize).pack(),
+    }
+}
+
+/// Instruction signed by `authority` on the oracle, for the instructions
+/// taking the oracle and its authority as their only accounts.
+fn authority_instruction(oracle: &Pubkey, authority: &Pubkey, instruction: OracleInstruction) -> Instruction {
+    Instruction {
+        program_id: custom_oracle::id(),
+        accounts: vec![
+            AccountMeta::new(*oracle, false),
+            AccountMeta::new_readonly(*authority, true),
+        ],
+        data: instruction.pack(),
+    }
+}
+
+/// Like [authority_instruction] for the update instructions, followed by the
+/// config PDA checked for a global pause.
+fn authority_update_instruction(
+    oracle: &Pubkey,
+    authority: &Pubkey,
+    instruction: OracleInstruction,
+) -> Instruction {
+    let mut instruction = authority_instruction(oracle, authority, instruction);
+    instruction.accounts.push(AccountMeta::new_readonly(
+        find_config_address(&custom_oracle::id()).0,
+        false,
+    ));
+    instruction
+}
+
+fn publish_component_instruction(
+    oracle: &Pubkey,
+    authority: &Pubkey,
+    publisher: &Pubkey,
+    index: u8,
+    price: i64,
+) -> Instruction {
+    Instruction {
+        program_id: custom_oracle::id(),
+        accounts: vec![
+            AccountMeta::new(*oracle, false),
+            AccountMeta::new_readonly(*authority, true),
+            AccountMeta::new_readonly(*publisher, true),
+            AccountMeta::new_readonly(find_config_address(&custom_oracle::id()).0, false),
+        ],
+        data: OracleInstruction::PublishComponent(PublishComponent {
+            index,
+            price,
+            confidence: 10,
+        })
+        .pack(),
+    }
+}
+
+/// `update` signed off-chain by `authority` at `slot`: the ed25519 program
+/// instruction verifying the signature, followed by the `UpdateSigned`.
+fn signed_update_instructions(
+    oracle: &Pubkey,
+    authority: &Keypair,
+    update: Update,
+    slot: u64,
+) -> [Instruction; 2] {
+    let update_signed = UpdateSigned { update, slot };
+    let message = update_signed.message(oracle);
+    let signature = authority.sign_message(&message);
+    // one offsets record pointing into this instruction: pubkey, signature, message
+    let (pubkey_offset, signature_offset) = (16u16, 48u16);
+    let message_offset = signature_offset + 64;
+    let mut data = vec![1, 0];
+    for value in [
+        signature_offset,
+        u16::MAX,
+        pubkey_offset,
+        u16::MAX,
+        message_offset,
+        message.len() as u16,
+        u16::MAX,
+    ] {
+        data.extend_from_slice(&value.to_le_bytes());
+    }
+    data.extend_from_slice(authority.pubkey().as_ref());
+    data.extend_from_slice(signature.as_ref());
+    data.extend_from_slice(&message);
+    [
+        Instruction {
+            program_id: ed25519_program::id(),
+            accounts: vec![],
+            data,
+        },
+        Instruction {
+            program_id: custom_oracle::id(),
+            accounts: vec![
+                AccountMeta::new(*oracle, false),
+                AccountMeta::new_readonly(sysvar::instructions::id(), false),
+                AccountMeta::new_readonly(find_config_address(&custom_oracle::id()).0, false),
+            ],
+            data: OracleInstruction::UpdateSigned(update_signed).pack(),
+        },
+    ]
+}
+
+/// Initialize data for a single quoter with every option off except `flags`.
+fn initialize_with_flags(flags: OracleFlags) -> Initialize {
+    Initialize {
+        min_confidence: 0,
+        reject_below_min_confidence: false,
+        flags,
+        base_decimals: 0,
+        quote_decimals: 0,
+        aggregation_trim: 0,
+        initial_price: 0,
+        max_staleness_slots: 0,
+        valid_slot_delay: 0,
+        num_quoters: 1,
+    }
+}
+
+/// Overwrites the program config PDA as a `Pause` or `Unpause` would.
+fn set_paused(context: &mut ProgramTestContext, paused: bool) {
+    let mut data = vec![0; ProgramConfig::LEN];
+    ProgramConfig {
+        is_initialized: true,
+        paused,
+    }
+    .pack_into_slice(&mut data);
+    context.set_account(
+        &find_config_address(&custom_oracle::id()).0,
+        &AccountSharedData::from(Account {
+            lamports: 1_000_000_000,
+            data,
+            owner: custom_oracle::id(),
+            ..Account::default()
+        }),
+    );
+}
+
+/// Sends `instructions` in one transaction paid for by the context's payer.
+async fn send(
+    context: &mut ProgramTestContext,
+    instructions: &[Instruction],
+    signers: &[&Keypair],
+) -> Result<(), TransactionError> {
+    let mut all_signers = vec![&context.payer];
+    all_signers.extend_from_slice(signers);
+    let transaction = Transaction::new_signed_with_payer(
+        instructions,
+        Some(&context.payer.pubkey()),
+        &all_signers,
+        context.last_blockhash,
+    );
+    context
+        .banks_client
+        .process_transaction(transaction)
+        .await
+        .map_err(|error| error.unwrap())
+}
+
+async fn account_data(context: &mut ProgramTestContext, oracle: &Pubkey) -> Vec<u8> {
+    let account = context
+        .banks_client
+        .get_account(*oracle)
+        .await
+        .unwrap()
+        .unwrap();
+    account.data
+}
+
+async fn oracle_state(context: &mut ProgramTestContext, oracle: &Pubkey) -> OracleV1 {
+    OracleV1::unpack_unchecked(&account_data(context, oracle).await).unwrap()
+}
+
+#[tokio::test]
+async fn test_update_writes_account() {
+    let oracle = Keypair::new();
+    let mut context = start(&oracle.pubkey()).await;
+
+    let transaction = Transaction::new_signed_with_payer(
+        &[update_instruction(
+            &oracle.pubkey(),
+            true,
+            Update {
+                price: 1_000_000,
+                confidence: 100,
+                status: PriceStatus::Trading.into(),
+            },
+        )],
+        Some(&context.payer.pubkey()),
+        &[&context.payer, &oracle],
+        context.last_blockhash,
+    );
+    context
+        .banks_client
+        .process_transaction(transaction)
+        .await
+        .unwrap();
+
+    let account = context
+        .banks_client
+        .get_account(oracle.pubkey())
+        .await
+        .unwrap()
+        .unwrap();
+    let state = OracleV1::unpack_unchecked(&account.data).unwrap();
+    assert_eq!(state.price_component(), 1_000_000);
+    assert_eq!(state.status(), u32::from(PriceStatus::Trading));
+    assert!(state.publish_slot() >= SLOT);
+}
+
+#[tokio::test]
+async fn test_update_rejects_unsigned_account() {
+    let oracle = Keypair::new();
+    let mut context = start(&oracle.pubkey()).await;
+
+    let transaction = Transaction::new_signed_with_payer(
+        &[update_instruction(
+            &oracle.pubkey(),
+            false,
+            Update {
+                price: 1_000_000,
+                confidence: 100,
+                status: PriceStatus::Trading.into(),
+            },
+        )],
+        Some(&context.payer.pubkey()),
+        &[&context.payer],
+        context.last_blockhash,
+    );
+    let error = context
+        .banks_client
+        .process_transaction(transaction)
+        .await
+        .unwrap_err()
+        .unwrap();
+    assert_eq!(
+        error,
+        TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(OracleError::IncorrectSigner as u32)
+        )
+    );
+}
+
+#[tokio::test]
+async fn test_update_rejects_account_owned_by_another_program() {
+    let oracle = Keypair::new();
+    let mut context = start(&oracle.pubkey()).await;
+    context.set_account(
+        &oracle.pubkey(),
+        &AccountSharedData::from(Account {
+            lamports: 1_000_000_000,
+            data: vec![0; OracleV1::LEN],
+            owner: Pubkey::new_unique(),
+            ..Account::default()
+        }),
+    );
+
+    let error = send(
+        &mut context,
+        &[update_instruction(
+            &oracle.pubkey(),
+            true,
+            Update {
+                price: 1_000_000,
+                confidence: 100,
+                status: PriceStatus::Trading.into(),
+            },
+        )],
+        &[&oracle],
+    )
+    .await
+    .unwrap_err();
+    assert_eq!(
+        error,
+        TransactionError::InstructionError(0, InstructionError::IncorrectProgramId)
+    );
+}
+
+#[tokio::test]
+async fn test_update_rejects_readonly_account() {
+    let oracle = Keypair::new();
+    let mut context = start(&oracle.pubkey()).await;
+
+    let mut instruction = update_instruction(
+        &oracle.pubkey(),
+        true,
+        Update {
+            price: 1_000_000,
+            confidence: 100,
+            status: PriceStatus::Trading.into(),
+        },
+    );
+    instruction.accounts[0] = AccountMeta::new_readonly(oracle.pubkey(), true);
+    let error = send(&mut context, &[instruction], &[&oracle]).await.unwrap_err();
+    assert_eq!(
+        error,
+        TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(OracleError::AccountNotWritable as u32)
+        )
+    );
+    let data = account_data(&mut context, &oracle.pubkey()).await;
+    assert!(data.iter().all(|&byte| byte == 0));
+}
+
+#[cfg(feature = "test-helpers")]
+#[tokio::test]
+async fn test_update_with_slot_stores_given_slot() {
+    use custom_oracle::instruction::UpdateWithSlot;
+
+    const FIXED_SLOT: u64 = 12_345;
+    let oracle = Keypair::new();
+    let mut context = start(&oracle.pubkey()).await;
+
+    let transaction = Transaction::new_signed_with_payer(
+        &[Instruction {
+            program_id: custom_oracle::id(),
+            accounts: vec![AccountMeta::new(oracle.pubkey(), true)],
+            data: OracleInstruction::UpdateWithSlot(UpdateWithSlot {
+                update: Update {
+                    price: 1_000_000,
+                    confidence: 100,
+                    status: PriceStatus::Trading.into(),
+                },
+                slot: FIXED_SLOT,
+            })
+            .pack(),
+        }],
+        Some(&context.payer.pubkey()),
+        &[&context.payer, &oracle],
+        context.last_blockhash,
+    );
+    context
+        .banks_client
+        .process_transaction(transaction)
+        .await
+        .unwrap();
+
+    let account = context
+        .banks_client
+        .get_account(oracle.pubkey())
+        .await
+        .unwrap()
+        .unwrap();
+    let state = OracleV1::unpack_unchecked(&account.data).unwrap();
+    assert_eq!(state.publish_slot(), FIXED_SLOT);
+    assert_eq!(state.last_slot(), FIXED_SLOT);
+}
+
+#[tokio::test]
+async fn test_update_with_authority_signs_for_the_oracle() {
+    let oracle = Keypair::new();
+    let authority = Keypair::new();
+    let stranger = Keypair::new();
+    let mut context = start(&oracle.pubkey()).await;
+    send(
+        &mut context,
+        &[initialize_instruction(
+            &oracle.pubkey(),
+            &authority.pubkey(),
+            initialize_with_flags(OracleFlags::empty()),
+        )],
+        &[&oracle],
+    )
+    .await
+    .unwrap();
+    let update = Update {
+        price: 1_000_000,
+        confidence: 100,
+        status: PriceStatus::Trading.into(),
+    };
+
+    let error = send(
+        &mut context,
+        &[authority_update_instruction(
+            &oracle.pubkey(),
+            &stranger.pubkey(),
+            OracleInstruction::UpdateWithAuthority(update),
+        )],
+        &[&stranger],
+    )
+    .await
+    .unwrap_err();
+    assert_eq!(
+        error,
+        TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(OracleError::IncorrectAuthority as u32)
+        )
+    );
+
+    // the oracle account itself doesn't sign
+    send(
+        &mut context,
+        &[authority_update_instruction(
+            &oracle.pubkey(),
+            &authority.pubkey(),
+            OracleInstruction::UpdateWithAuthority(update),
+        )],
+        &[&authority],
+    )
+    .await
+    .unwrap();
+    let state = oracle_state(&mut context, &oracle.pubkey()).await;
+    assert_eq!(state.price_component, 1_000_000);
+    assert_eq!(state.confidence_component, 100);
+    assert_eq!(state.publish_slot, SLOT);
+}
+
+#[tokio::test]
+async fn test_seeded_ema_is_not_dragged_from_zero() {
+    let oracle = Keypair::new();
+    let authority = Keypair::new();
+    let mut context = start(&oracle.pubkey()).await;
+    send(
+        &mut context,
+        &[initialize_instruction(
+            &oracle.pubkey(),
+            &authority.pubkey(),
+            Initialize {
+                initial_price: 1_000_000,
+                ..initialize_with_flags(OracleFlags::EMA_ENABLED)
+            },
+        )],
+        &[&oracle],
+    )
+    .await
+    .unwrap();
+    let state = oracle_state(&mut context, &oracle.pubkey()).await;
+    assert_eq!(state.ema_price_value, 1_000_000);
+    assert!(!state.has_price());
+
+    send(
+        &mut context,
+        &[update_instruction(
+            &oracle.pubkey(),
+            true,
+            Update {
+                price: 1_030_000,
+                confidence: 100,
+                status: PriceStatus::Trading.into(),
+            },
+        )],
+        &[&oracle],
+    )
+    .await
+    .unwrap();
+    let state = oracle_state(&mut context, &oracle.pubkey()).await;
+    let ema = state.ema_price_value as i64;
+    // the seed weighs as one earlier sample, so the ema lands between the seed
+    // and the new price
+    assert!((1_000_000..1_030_000).contains(&ema), "ema {}", ema);
+}
+
+#[tokio::test]
+async fn test_update_pct_confidence_converts_to_absolute() {
+    let oracle = Keypair::new();
+    let mut context = start(&oracle.pubkey()).await;
+
+    // 1% of 150.00000000
+    send(
+        &mut context,
+        &[signed_oracle_instruction(
+            &oracle.pubkey(),
+            OracleInstruction::UpdatePctConfidence(UpdatePctConfidence {
+                price: 15_000_000_000,
+                confidence_bps: 100,
+                status: PriceStatus::Trading.into(),
+            }),
+        )],
+        &[&oracle],
+    )
+    .await
+    .unwrap();
+    let state = oracle_state(&mut context, &oracle.pubkey()).await;
+    assert_eq!(state.price_component, 15_000_000_000);
+    assert_eq!(state.confidence_component, 150_000_000);
+    assert_eq!(state.status, u32::from(PriceStatus::Trading));
+
+    // measured against the price's magnitude
+    send(
+        &mut context,
+        &[signed_oracle_instruction(
+            &oracle.pubkey(),
+            OracleInstruction::UpdatePctConfidence(UpdatePctConfidence {
+                price: -15_000_000_000,
+                confidence_bps: 100,
+                status: PriceStatus::Trading.into(),
+            }),
+        )],
+        &[&oracle],
+    )
+    .await
+    .unwrap();
+    let state = oracle_state(&mut context, &oracle.pubkey()).await;
+    assert_eq!(state.confidence_component, 150_000_000);
+}
+
+#[tokio::test]
+async fn test_sequence_increments_by_one_across_status_changes() {
+    let statuses = [
+        PriceStatus::Trading,
+        PriceStatus::Halted,
+        PriceStatus::Unknown,
+        PriceStatus::Trading,
+        PriceStatus::Auction,
+    ];
+    for flags in [OracleFlags::empty(), OracleFlags::SKIP_BUFFER] {
+        let oracle = Keypair::new();
+        let mut context = start(&oracle.pubkey()).await;
+        send(
+            &mut context,
+            &[initialize_instruction(
+                &oracle.pubkey(),
+                &Pubkey::new_unique(),
+                initialize_with_flags(flags),
+            )],
+            &[&oracle],
+        )
+        .await
+        .unwrap();
+        assert_eq!(oracle_state(&mut context, &oracle.pubkey()).await.sequence(), 0);
+
+        for (i, status) in statuses.iter().enumerate() {
+            let update = Update {
+                price: 1_000_000 + i as i64,
+                confidence: 100,
+                status: (*status).into(),
+            };
+            send(
+                &mut context,
+                &[update_instruction(&oracle.pubkey(), true, update)],
+                &[&oracle],
+            )
+            .await
+            .unwrap();
+            let state = oracle_state(&mut context, &oracle.pubkey()).await;
+            assert_eq!(state.sequence(), i as u64 + 1, "{:?}", flags);
+            assert_eq!(state.status(), u32::from(*status));
+        }
+    }
+}
+
+#[tokio::test]
+async fn test_update_count_survives_status_changes() {
+    let statuses = [
+        PriceStatus::Trading,
+        PriceStatus::Halted,
+        PriceStatus::Trading,
+        PriceStatus::Unknown,
+    ];
+    for flags in [OracleFlags::empty(), OracleFlags::SKIP_BUFFER] {
+        let oracle = Keypair::new();
+        let mut context = start(&oracle.pubkey()).await;
+        send(
+            &mut context,
+            &[initialize_instruction(
+                &oracle.pubkey(),
+                &Pubkey::new_unique(),
+                initialize_with_flags(flags),
+            )],
+            &[&oracle],
+        )
+        .await
+        .unwrap();
+
+        for (i, status) in statuses.iter().enumerate() {
+            let update = Update {
+                price: 2_000_000 + i as i64,
+                confidence: 100,
+                status: (*status).into(),
+            };
+            send(
+                &mut context,
+                &[update_instruction(&oracle.pubkey(), true, update)],
+                &[&oracle],
+            )
+            .await
+            .unwrap();
+        }
+        let state = oracle_state(&mut context, &oracle.pubkey()).await;
+        assert_eq!(state.update_count(), statuses.len() as u64, "{:?}", flags);
+        assert_eq!(state.status(), u32::from(PriceStatus::Unknown));
+    }
+}
+
+#[tokio::test]
+async fn test_update_quote_derives_mid_and_half_spread() {
+    for flags in [OracleFlags::empty(), OracleFlags::SKIP_BUFFER] {
+        let oracle = Keypair::new();
+        let mut context = start(&oracle.pubkey()).await;
+        send(
+            &mut context,
+            &[initialize_instruction(
+                &oracle.pubkey(),
+                &Pubkey::new_unique(),
+                initialize_with_flags(flags),
+            )],
+            &[&oracle],
+        )
+        .await
+        .unwrap();
+
+        // (bid, ask, mid, half spread), odd spreads rounding toward zero
+        let quotes = [
+            (99_000_000, 101_000_000, 100_000_000, 1_000_000),
+            (1_000, 1_003, 1_001, 1),
+            (-10_000, -8_000, -9_000, 1_000),
+        ];
+        for &(bid, ask, mid, half_spread) in quotes.iter() {
+            send(
+                &mut context,
+                &[signed_oracle_instruction(
+                    &oracle.pubkey(),
+                    OracleInstruction::UpdateQuote(UpdateQuote {
+                        bid,
+                        ask,
+                        status: PriceStatus::Trading.into(),
+                    }),
+                )],
+                &[&oracle],
+            )
+            .await
+            .unwrap();
+            let state = oracle_state(&mut context, &oracle.pubkey()).await;
+            assert_eq!(state.price_component(), mid, "{:?}", flags);
+            assert_eq!(state.confidence_component(), half_spread);
+            assert_eq!((state.bid(), state.ask()), (bid, ask));
+        }
+    }
+}
+
+#[tokio::test]
+async fn test_update_quote_rejects_crossed_quote() {
+    let oracle = Keypair::new();
+    let mut context = start(&oracle.pubkey()).await;
+    let error = send(
+        &mut context,
+        &[signed_oracle_instruction(
+            &oracle.pubkey(),
+            OracleInstruction::UpdateQuote(UpdateQuote {
+                bid: 101,
+                ask: 99,
+                status: PriceStatus::Trading.into(),
+            }),
+        )],
+        &[&oracle],
+    )
+    .await
+    .unwrap_err();
+    assert_eq!(
+        error,
+        TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(OracleError::InvalidPrice as u32)
+        )
+    );
+}
+
+#[tokio::test]
+async fn test_update_confidence_keeps_price() {
+    let oracle = Keypair::new();
+    let authority = Keypair::new();
+    let mut context = start(&oracle.pubkey()).await;
+    send(
+        &mut context,
+        &[
+            initialize_instruction(
+                &oracle.pubkey(),
+                &authority.pubkey(),
+                initialize_with_flags(OracleFlags::empty()),
+            ),
+            update_instruction(
+                &oracle.pubkey(),
+                true,
+                Update {
+                    price: 1_000,
+                    confidence: 10,
+                    status: PriceStatus::Trading.into(),
+                },
+            ),
+        ],
+        &[&oracle],
+    )
+    .await
+    .unwrap();
+    let prior = oracle_state(&mut context, &oracle.pubkey()).await;
+    assert_eq!(prior.publish_slot, SLOT);
+
+    context.warp_to_slot(SLOT + 50).unwrap();
+    send(
+        &mut context,
+        &[signed_oracle_instruction(
+            &oracle.pubkey(),
+            OracleInstruction::UpdateConfidence(UpdateConfidence {
+                confidence: 200,
+                status: PriceStatus::Trading.into(),
+            }),
+        )],
+        &[&oracle],
+    )
+    .await
+    .unwrap();
+
+    let state = oracle_state(&mut context, &oracle.pubkey()).await;
+    assert_eq!(state.price_component, 1_000);
+    assert_eq!(state.confidence_component, 200);
+    assert_eq!(state.status, u32::from(PriceStatus::Trading));
+    assert_eq!(state.publish_slot, SLOT + 50);
+    assert!(state.timestamp >= prior.timestamp);
+    assert_eq!(state.sequence(), prior.sequence() + 1);
+}
+
+#[tokio::test]
+async fn test_update_confidence_as_wide_as_the_price_is_rejected() {
+    let oracle = Keypair::new();
+    let authority = Keypair::new();
+    let mut context = start(&oracle.pubkey()).await;
+    send(
+        &mut context,
+        &[
+            initialize_instruction(
+                &oracle.pubkey(),
+                &authority.pubkey(),
+                initialize_with_flags(OracleFlags::empty()),
+            ),
+            update_instruction(
+                &oracle.pubkey(),
+                true,
+                Update {
+                    price: 1_000,
+                    confidence: 10,
+                    status: PriceStatus::Trading.into(),
+                },
+            ),
+        ],
+        &[&oracle],
+    )
+    .await
+    .unwrap();
+    let before = account_data(&mut context, &oracle.pubkey()).await;
+
+    let error = send(
+        &mut context,
+        &[signed_oracle_instruction(
+            &oracle.pubkey(),
+            OracleInstruction::UpdateConfidence(UpdateConfidence {
+                confidence: 1_000,
+                status: PriceStatus::Trading.into(),
+            }),
+        )],
+        &[&oracle],
+    )
+    .await
+    .unwrap_err();
+    assert_eq!(
+        error,
+        TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(OracleError::UntrustedPrice as u32)
+        )
+    );
+    assert_eq!(account_data(&mut context, &oracle.pubkey()).await, before);
+}
+
+#[tokio::test]
+async fn test_signed_updates_cannot_be_replayed() {
+    let oracle = Keypair::new();
+    let authority = Keypair::new();
+    let mut context = start(&oracle.pubkey()).await;
+    send(
+        &mut context,
+        &[initialize_instruction(
+            &oracle.pubkey(),
+            &authority.pubkey(),
+            initialize_with_flags(OracleFlags::empty()),
+        )],
+        &[&oracle],
+    )
+    .await
+    .unwrap();
+    let update = |price, confidence| Update {
+        price,
+        confidence,
+        status: PriceStatus::Trading.into(),
+    };
+    let signed = |update, slot| signed_update_instructions(&oracle.pubkey(), &authority, update, slot);
+    let replayed = |instructions: &[Instruction]| [instructions, instructions].concat();
+
+    // a copy later in the same transaction is caught once the first lands
+    let first = signed(update(1_000, 10), SLOT);
+    let error = send(&mut context, &replayed(&first), &[]).await.unwrap_err();
+    assert_eq!(
+        error,
+        TransactionError::InstructionError(
+            3,
+            InstructionError::Custom(OracleError::InvalidSignature as u32)
+        )
+    );
+    send(&mut context, &first, &[]).await.unwrap();
+    assert_eq!(oracle_state(&mut context, &oracle.pubkey()).await.publish_slot, SLOT);
+
+    // an update too uncertain to replace the price is rejected outright, so
+    // it never lands however often it's submitted
+    context.warp_to_slot(SLOT + 10).unwrap();
+    let before = account_data(&mut context, &oracle.pubkey()).await;
+    let untrusted = signed(update(1_000, 1_000), SLOT + 10);
+    for instructions in [untrusted.to_vec(), replayed(&untrusted)] {
+        let error = send(&mut context, &instructions, &[]).await.unwrap_err();
+        assert_eq!(
+            error,
+            TransactionError::InstructionError(
+                1,
+                InstructionError::Custom(OracleError::UntrustedPrice as u32)
+            )
+        );
+    }
+    assert_eq!(account_data(&mut context, &oracle.pubkey()).await, before);
+
+    // the slot it carried is still open to a trusted update, which can't be
+    // replayed either
+    let second = signed(update(1_100, 10), SLOT + 10);
+    send(&mut context, &second, &[]).await.unwrap();
+    let state = oracle_state(&mut context, &oracle.pubkey()).await;
+    assert_eq!(state.price_component, 1_100);
+    assert_eq!(state.publish_slot, SLOT + 10);
+    for instructions in [replayed(&second), [&first[..], &second, &first].concat()] {
+        let error = send(&mut context, &instructions, &[]).await.unwrap_err();
+        assert_eq!(
+            error,
+            TransactionError::InstructionError(
+                1,
+                InstructionError::Custom(OracleError::InvalidSignature as u32)
+            )
+        );
+    }
+}
+
+#[tokio::test]
+async fn test_updates_fail_while_paused_and_resume_after_unpause() {
+    let oracle = Keypair::new();
+    let mut context = start(&oracle.pubkey()).await;
+    let update = |price| Update {
+        price,
+        confidence: 100,
+        status: PriceStatus::Trading.into(),
+    };
+
+    set_paused(&mut context, true);
+    let error = send(
+        &mut context,
+        &[update_instruction(&oracle.pubkey(), true, update(1_000_000))],
+        &[&oracle],
+    )
+    .await
+    .unwrap_err();
+    assert_eq!(
+        error,
+        TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(OracleError::ProgramPaused as u32)
+        )
+    );
+
+    set_paused(&mut context, false);
+    send(
+        &mut context,
+        &[update_instruction(&oracle.pubkey(), true, update(2_000_000))],
+        &[&oracle],
+    )
+    .await
+    .unwrap();
+    let state = oracle_state(&mut context, &oracle.pubkey()).await;
+    assert_eq!(state.price_component(), 2_000_000);
+}
+
+#[tokio::test]
+async fn test_update_without_config_account_is_rejected() {
+    let oracle = Keypair::new();
+    let mut context = start(&oracle.pubkey()).await;
+    let update = OracleInstruction::Update(Update {
+        price: 1_000_000,
+        confidence: 100,
+        status: PriceStatus::Trading.into(),
+    })
+    .pack();
+
+    // neither leaving the PDA out nor passing another account skips the pause
+    for accounts in [
+        vec![AccountMeta::new(oracle.pubkey(), true)],
+        vec![
+            AccountMeta::new(oracle.pubkey(), true),
+            AccountMeta::new_readonly(Pubkey::new_unique(), false),
+        ],
+    ] {
+        let error = send(
+            &mut context,
+            &[Instruction {
+                program_id: custom_oracle::id(),
+                accounts,
+                data: update.clone(),
+            }],
+            &[&oracle],
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(
+            error,
+            TransactionError::InstructionError(0, InstructionError::NotEnoughAccountKeys)
+        );
+    }
+    let state = oracle_state(&mut context, &oracle.pubkey()).await;
+    assert_eq!(state.price_component(), 0);
+}
+
+#[tokio::test]
+async fn test_initialize_rejects_underfunded_account() {
+    let oracle = Keypair::new();
+    let authority = Keypair::new();
+    let mut context = start(&oracle.pubkey()).await;
+    let rent = context.banks_client.get_rent().await.unwrap();
+    let minimum_balance = rent.minimum_balance(OracleV1::LEN);
+    context.set_account(
+        &oracle.pubkey(),
+        &AccountSharedData::from(Account {
+            lamports: minimum_balance - 1,
+            data: vec![0; OracleV1::LEN],
+            owner: custom_oracle::id(),
+            ..Account::default()
+        }),
+    );
+
+    let error = send(
+        &mut context,
+        &[initialize_instruction(
+            &oracle.pubkey(),
+            &authority.pubkey(),
+            initialize_with_flags(OracleFlags::empty()),
+        )],
+        &[&oracle],
+    )
+    .await
+    .unwrap_err();
+    assert_eq!(
+        error,
+        TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(OracleError::NotRentExempt as u32)
+        )
+    );
+    let data = account_data(&mut context, &oracle.pubkey()).await;
+    assert!(data.iter().all(|&byte| byte == 0));
+}
+
+#[tokio::test]
+async fn test_initialize_rejects_zero_quoters() {
+    let oracle = Keypair::new();
+    let mut context = start(&oracle.pubkey()).await;
+    let authority = Pubkey::new_unique();
+
+    let error = send(
+        &mut context,
+        &[initialize_instruction(
+            &oracle.pubkey(),
+            &authority,
+            Initialize {
+                num_quoters: 0,
+                ..initialize_with_flags(OracleFlags::empty())
+            },
+        )],
+        &[&oracle],
+    )
+    .await
+    .unwrap_err();
+    assert_eq!(
+        error,
+        TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(OracleError::InvalidInstruction as u32)
+        )
+    );
+
+    send(
+        &mut context,
+        &[initialize_instruction(
+            &oracle.pubkey(),
+            &authority,
+            Initialize {
+                num_quoters: 3,
+                ..initialize_with_flags(OracleFlags::empty())
+            },
+        )],
+        &[&oracle],
+    )
+    .await
+    .unwrap();
+    assert_eq!(oracle_state(&mut context, &oracle.pubkey()).await.num_quoters, 3);
+}
+
+#[tokio::test]
+async fn test_publishers_keep_num_quoters_in_sync() {
+    let oracle = Keypair::new();
+    let authority = Keypair::new();
+    let publishers = [Keypair::new(), Keypair::new()];
+    let mut context = start(&oracle.pubkey()).await;
+    send(
+        &mut context,
+        &[initialize_instruction(
+            &oracle.pubkey(),
+            &authority.pubkey(),
+            initialize_with_flags(OracleFlags::empty()),
+        )],
+        &[&oracle],
+    )
+    .await
+    .unwrap();
+
+    for (index, publisher) in publishers.iter().enumerate() {
+        let add = OracleInstruction::AddPublisher(AddPublisher {
+            index: index as u8,
+            publisher: publisher.pubkey(),
+        });
+        send(
+            &mut context,
+            &[authority_instruction(&oracle.pubkey(), &authority.pubkey(), add)],
+            &[&authority],
+        )
+        .await
+        .unwrap();
+        let state = oracle_state(&mut context, &oracle.pubkey()).await;
+        assert_eq!(state.num_quoters, index as u32 + 1);
+        assert!(state.verify_quoter_count());
+    }
+
+    // a publisher can't hold two components
+    let add = OracleInstruction::AddPublisher(AddPublisher {
+        index: 2,
+        publisher: publishers[0].pubkey(),
+    });
+    let error = send(
+        &mut context,
+        &[authority_instruction(&oracle.pubkey(), &authority.pubkey(), add)],
+        &[&authority],
+    )
+    .await
+    .unwrap_err();
+    assert_eq!(
+        error,
+        TransactionError::InstructionError(0, InstructionError::InvalidArgument)
+    );
+
+    let remove = OracleInstruction::RemovePublisher { index: 0 };
+    send(
+        &mut context,
+        &[authority_instruction(&oracle.pubkey(), &authority.pubkey(), remove)],
+        &[&authority],
+    )
+    .await
+    .unwrap();
+    let state = oracle_state(&mut context, &oracle.pubkey()).await;
+    assert_eq!(state.num_quoters, 1);
+    assert!(state.verify_quoter_count());
+    assert_eq!(state.component_publisher(0), Pubkey::default());
+
+    // emptying the allowlist keeps a quoter count of one
+    let remove = OracleInstruction::RemovePublisher { index: 1 };
+    send(
+        &mut context,
+        &[authority_instruction(&oracle.pubkey(), &authority.pubkey(), remove)],
+        &[&authority],
+    )
+    .await
+    .unwrap();
+    let state = oracle_state(&mut context, &oracle.pubkey()).await;
+    assert_eq!(state.num_quoters, 1);
+    assert!(state.component_publishers().is_empty());
+}
+
+#[tokio::test]
+async fn test_publish_component_requires_allowlisted_publisher() {
+    let oracle = Keypair::new();
+    let authority = Keypair::new();
+    let publisher = Keypair::new();
+    let stranger = Keypair::new();
+    let mut context = start(&oracle.pubkey()).await;
+    let add = OracleInstruction::AddPublisher(AddPublisher {
+        index: 0,
+        publisher: publisher.pubkey(),
+    });
+    send(
+        &mut context,
+        &[
+            initialize_instruction(
+                &oracle.pubkey(),
+                &authority.pubkey(),
+                initialize_with_flags(OracleFlags::empty()),
+            ),
+            authority_instruction(&oracle.pubkey(), &authority.pubkey(), add),
+        ],
+        &[&oracle, &authority],
+    )
+    .await
+    .unwrap();
+
+    let error = send(
+        &mut context,
+        &[publish_component_instruction(
+            &oracle.pubkey(),
+            &authority.pubkey(),
+            &stranger.pubkey(),
+            0,
+            1_000,
+        )],
+        &[&authority, &stranger],
+    )
+    .await
+    .unwrap_err();
+    assert_eq!(
+        error,
+        TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(OracleError::IncorrectAuthority as u32)
+        )
+    );
+
+    send(
+        &mut context,
+        &[publish_component_instruction(
+            &oracle.pubkey(),
+            &authority.pubkey(),
+            &publisher.pubkey(),
+            0,
+            1_000,
+        )],
+        &[&authority, &publisher],
+    )
+    .await
+    .unwrap();
+    let state = oracle_state(&mut context, &oracle.pubkey()).await;
+    assert_eq!(state.component(0), (1_000, 10));
+    assert_eq!(state.component_publishers(), vec![(publisher.pubkey(), 1_000)]);
+}
+
+#[tokio::test]
+async fn test_publish_component_rejects_out_of_range_index() {
+    let oracle = Keypair::new();
+    let authority = Keypair::new();
+    let publisher = Keypair::new();
+    let mut context = start(&oracle.pubkey()).await;
+    send(
+        &mut context,
+        &[initialize_instruction(
+            &oracle.pubkey(),
+            &authority.pubkey(),
+            initialize_with_flags(OracleFlags::empty()),
+        )],
+        &[&oracle],
+    )
+    .await
+    .unwrap();
+
+    let error = send(
+        &mut context,
+        &[publish_component_instruction(
+            &oracle.pubkey(),
+            &authority.pubkey(),
+            &publisher.pubkey(),
+            200,
+            1_000,
+        )],
+        &[&authority, &publisher],
+    )
+    .await
+    .unwrap_err();
+    assert_eq!(
+        error,
+        TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(OracleError::ComponentIndexOutOfRange as u32)
+        )
+    );
+}
+
+#[tokio::test]
+async fn test_oversized_account_is_usable_after_initialize() {
+    let oracle = Keypair::new();
+    let authority = Keypair::new();
+    let mut context = start(&oracle.pubkey()).await;
+    let mut data = vec![0; 4_000];
+    data[OracleV1::LEN..].fill(0xee);
+    context.set_account(
+        &oracle.pubkey(),
+        &AccountSharedData::from(Account {
+            lamports: 1_000_000_000,
+            data,
+            owner: custom_oracle::id(),
+            ..Account::default()
+        }),
+    );
+    send(
+        &mut context,
+        &[
+            initialize_instruction(
+                &oracle.pubkey(),
+                &authority.pubkey(),
+                initialize_with_flags(OracleFlags::empty()),
+            ),
+            update_instruction(
+                &oracle.pubkey(),
+                true,
+                Update {
+                    price: 1_000_000,
+                    confidence: 100,
+                    status: PriceStatus::Trading.into(),
+                },
+            ),
+            authority_instruction(
+                &oracle.pubkey(),
+                &authority.pubkey(),
+                OracleInstruction::SetMetadata { metadata: [3; 32] },
+            ),
+        ],
+        &[&oracle, &authority],
+    )
+    .await
+    .unwrap();
+
+    let data = account_data(&mut context, &oracle.pubkey()).await;
+    let state = OracleV1::unpack_unchecked(&data[..OracleV1::LEN]).unwrap();
+    assert_eq!(state.price_component, 1_000_000);
+    assert_eq!(state.metadata(), [3; 32]);
+    // the padding past the oracle is left alone
+    assert!(data[OracleV1::LEN..].iter().all(|&byte| byte == 0xee));
+}
+
+#[tokio::test]
+async fn test_update_wide_stores_price_above_i64() {
+    let oracle = Keypair::new();
+    let authority = Keypair::new();
+    let mut context = start(&oracle.pubkey()).await;
+    context.set_account(
+        &oracle.pubkey(),
+        &AccountSharedData::from(Account {
+            lamports: 1_000_000_000,
+            data: vec![0; OracleV2::LEN],
+            owner: custom_oracle::id(),
+            ..Account::default()
+        }),
+    );
+    let price = i64::MAX as i128 * 4;
+    send(
+        &mut context,
+        &[
+            initialize_instruction(
+                &oracle.pubkey(),
+                &authority.pubkey(),
+                initialize_with_flags(OracleFlags::empty()),
+            ),
+            signed_oracle_instruction(
+                &oracle.pubkey(),
+                OracleInstruction::UpdateWide(UpdateWide {
+                    price,
+                    confidence: 100,
+                    status: PriceStatus::Trading.into(),
+                }),
+            ),
+        ],
+        &[&oracle],
+    )
+    .await
+    .unwrap();
+
+    let data = account_data(&mut context, &oracle.pubkey()).await;
+    let state = OracleV2::unpack_unchecked(&data).unwrap();
+    assert_eq!(state.price, price);
+    assert_eq!(state.base.price_component, i64::MAX);
+    assert_eq!(state.base.status(), u32::from(PriceStatus::Trading));
+
+    let mut packed = vec![0; OracleV2::LEN];
+    state.pack_into_slice(&mut packed);
+    assert_eq!(packed, data);
+}
+
+#[tokio::test]
+async fn test_authority_and_update_instructions_keep_a_v2_account_wide() {
+    let oracle = Keypair::new();
+    let authority = Keypair::new();
+    let publisher = Keypair::new();
+    let mut context = start(&oracle.pubkey()).await;
+    context.set_account(
+        &oracle.pubkey(),
+        &AccountSharedData::from(Account {
+            lamports: 1_000_000_000,
+            data: vec![0; OracleV2::LEN],
+            owner: custom_oracle::id(),
+            ..Account::default()
+        }),
+    );
+    let price = i64::MAX as i128 * 4;
+    send(
+        &mut context,
+        &[
+            initialize_instruction(
+                &oracle.pubkey(),
+                &authority.pubkey(),
+                initialize_with_flags(OracleFlags::empty()),
+            ),
+            signed_oracle_instruction(
+                &oracle.pubkey(),
+                OracleInstruction::UpdateWide(UpdateWide {
+                    price,
+                    confidence: 100,
+                    status: PriceStatus::Trading.into(),
+                }),
+            ),
+        ],
+        &[&oracle],
+    )
+    .await
+    .unwrap();
+
+    let metadata = [7; 32];
+    send(
+        &mut context,
+        &[
+            authority_instruction(
+                &oracle.pubkey(),
+                &authority.pubkey(),
+                OracleInstruction::SetMetadata { metadata },
+            ),
+            authority_instruction(
+                &oracle.pubkey(),
+                &authority.pubkey(),
+                OracleInstruction::AddPublisher(AddPublisher {
+                    index: 0,
+                    publisher: publisher.pubkey(),
+                }),
+            ),
+        ],
+        &[&authority],
+    )
+    .await
+    .unwrap();
+
+    let state = OracleV2::unpack_unchecked(&account_data(&mut context, &oracle.pubkey()).await).unwrap();
+    assert_eq!(state.base.version, VERSION_V2);
+    assert_eq!(state.base.metadata(), metadata);
+    assert_eq!(state.base.num_quoters, 1);
+    assert_eq!(state.price, price);
+
+    // widening the confidence keeps the full-width price
+    send(
+        &mut context,
+        &[signed_oracle_instruction(
+            &oracle.pubkey(),
+            OracleInstruction::UpdateConfidence(UpdateConfidence {
+                confidence: 200,
+                status: PriceStatus::Trading.into(),
+            }),
+        )],
+        &[&oracle],
+    )
+    .await
+    .unwrap();
+    let state = OracleV2::unpack_unchecked(&account_data(&mut context, &oracle.pubkey()).await).unwrap();
+    assert_eq!(state.base.version, VERSION_V2);
+    assert_eq!(state.base.confidence_component, 200);
+    assert_eq!(state.price, price);
+
+    // a plain update narrows the wide price to its own
+    send(
+        &mut context,
+        &[update_instruction(
+            &oracle.pubkey(),
+            true,
+            Update {
+                price: 1_000,
+                confidence: 10,
+                status: PriceStatus::Trading.into(),
+            },
+        )],
+        &[&oracle],
+    )
+    .await
+    .unwrap();
+    let state = OracleV2::unpack_unchecked(&account_data(&mut context, &oracle.pubkey()).await).unwrap();
+    assert_eq!(state.base.version, VERSION_V2);
+    assert_eq!(state.base.price_component, 1_000);
+    assert_eq!(state.price, 1_000);
+}
+
+#[tokio::test]
+async fn test_updates_fail_once_frozen() {
+    let oracle = Keypair::new();
+    let authority = Keypair::new();
+    let stranger = Keypair::new();
+    let mut context = start(&oracle.pubkey()).await;
+    send(
+        &mut context,
+        &[initialize_instruction(
+            &oracle.pubkey(),
+            &authority.pubkey(),
+            initialize_with_flags(OracleFlags::empty()),
+        )],
+        &[&oracle],
+    )
+    .await
+    .unwrap();
+
+    // only the authority can freeze the oracle
+    let error = send(
+        &mut context,
+        &[authority_instruction(
+            &oracle.pubkey(),
+            &stranger.pubkey(),
+            OracleInstruction::PrepareTransfer,
+        )],
+        &[&stranger],
+    )
+    .await
+    .unwrap_err();
+    assert_eq!(
+        error,
+        TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(OracleError::IncorrectAuthority as u32)
+        )
+    );
+
+    send(
+        &mut context,
+        &[
+            update_instruction(
+                &oracle.pubkey(),
+                true,
+                Update {
+                    price: 1_000,
+                    confidence: 10,
+                    status: PriceStatus::Trading.into(),
+                },
+            ),
+            authority_instruction(
+                &oracle.pubkey(),
+                &authority.pubkey(),
+                OracleInstruction::PrepareTransfer,
+            ),
+        ],
+        &[&oracle, &authority],
+    )
+    .await
+    .unwrap();
+    let frozen = account_data(&mut context, &oracle.pubkey()).await;
+
+    for instruction in vec![
+        update_instruction(
+            &oracle.pubkey(),
+            true,
+            Update {
+                price: 2_000,
+                confidence: 10,
+                status: PriceStatus::Trading.into(),
+            },
+        ),
+        signed_oracle_instruction(
+            &oracle.pubkey(),
+            OracleInstruction::UpdateQuote(UpdateQuote {
+                bid: 1_900,
+                ask: 2_100,
+                status: PriceStatus::Trading.into(),
+            }),
+        ),
+    ]
+    .into_iter()
+    {
+        let error = send(&mut context, &[instruction], &[&oracle]).await.unwrap_err();
+        assert_eq!(
+            error,
+            TransactionError::InstructionError(
+                0,
+                InstructionError::Custom(OracleError::AccountFrozen as u32)
+            )
+        );
+    }
+    assert_eq!(account_data(&mut context, &oracle.pubkey()).await, frozen);
+    let state = oracle_state(&mut context, &oracle.pubkey()).await;
+    assert_eq!(state.price_component, 1_000);
+}
+
+#[tokio::test]
+async fn test_authority_instructions_reject_frozen_oracle() {
+    let oracle = Keypair::new();
+    let authority = Keypair::new();
+    let new_authority = Keypair::new();
+    let mut context = start(&oracle.pubkey()).await;
+    send(
+        &mut context,
+        &[
+            initialize_instruction(
+                &oracle.pubkey(),
+                &authority.pubkey(),
+                initialize_with_flags(OracleFlags::empty()),
+            ),
+            authority_instruction(
+                &oracle.pubkey(),
+                &authority.pubkey(),
+                OracleInstruction::ProposeAuthority {
+                    new_authority: new_authority.pubkey(),
+                },
+            ),
+            authority_instruction(
+                &oracle.pubkey(),
+                &authority.pubkey(),
+                OracleInstruction::PrepareTransfer,
+            ),
+        ],
+        &[&oracle, &authority],
+    )
+    .await
+    .unwrap();
+    let frozen = account_data(&mut context, &oracle.pubkey()).await;
+
+    for (instruction, signer) in vec![
+        (
+            OracleInstruction::AddPublisher(AddPublisher {
+                index: 0,
+                publisher: Pubkey::new_unique(),
+            }),
+            &authority,
+        ),
+        (OracleInstruction::SetMetadata { metadata: [1; 32] }, &authority),
+        (
+            OracleInstruction::ProposeAuthority {
+                new_authority: Pubkey::new_unique(),
+            },
+            &authority,
+        ),
+        (OracleInstruction::AcceptAuthority, &new_authority),
+        (
+            OracleInstruction::SetPriceType {
+                price_type: PriceType::Rate.into(),
+            },
+            &authority,
+        ),
+    ]
+    .into_iter()
+    {
+        let error = send(
+            &mut context,
+            &[authority_instruction(&oracle.pubkey(), &signer.pubkey(), instruction)],
+            &[signer],
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(
+            error,
+            TransactionError::InstructionError(
+                0,
+                InstructionError::Custom(OracleError::AccountFrozen as u32)
+            )
+        );
+    }
+    assert_eq!(account_data(&mut context, &oracle.pubkey()).await, frozen);
+}
+
+#[tokio::test]
+async fn test_clear_data_rejects_frozen_oracle() {
+    let oracle = Keypair::new();
+    let authority = Keypair::new();
+    let mut context = start(&oracle.pubkey()).await;
+    send(
+        &mut context,
+        &[
+            initialize_instruction(
+                &oracle.pubkey(),
+                &authority.pubkey(),
+                initialize_with_flags(OracleFlags::empty()),
+            ),
+            authority_instruction(
+                &oracle.pubkey(),
+                &authority.pubkey(),
+                OracleInstruction::PrepareTransfer,
+            ),
+        ],
+        &[&oracle, &authority],
+    )
+    .await
+    .unwrap();
+
+    let error = send(
+        &mut context,
+        &[authority_instruction(
+            &oracle.pubkey(),
+            &authority.pubkey(),
+            OracleInstruction::ClearData,
+        )],
+        &[&authority],
+    )
+    .await
+    .unwrap_err();
+    assert_eq!(
+        error,
+        TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(OracleError::AccountFrozen as u32)
+        )
+    );
+    let state = oracle_state(&mut context, &oracle.pubkey()).await;
+    assert!(state.flags().contains(OracleFlags::FROZEN));
+}
+
+#[tokio::test]
+async fn test_clear_data_zeroes_oracle() {
+    let oracle = Keypair::new();
+    let authority = Keypair::new();
+    let mut context = start(&oracle.pubkey()).await;
+    send(
+        &mut context,
+        &[
+            initialize_instruction(
+                &oracle.pubkey(),
+                &authority.pubkey(),
+                initialize_with_flags(OracleFlags::empty()),
+            ),
+            authority_instruction(
+                &oracle.pubkey(),
+                &authority.pubkey(),
+                OracleInstruction::ClearData,
+            ),
+        ],
+        &[&oracle, &authority],
+    )
+    .await
+    .unwrap();
+
+    let data = account_data(&mut context, &oracle.pubkey()).await;
+    assert!(OracleV1::is_zeroed(&data));
+    let state = OracleV1::unpack_unchecked(&data).unwrap();
+    assert!(!state.is_initialized());
+}
+
+#[tokio::test]
+async fn test_batch_backfills_history() {
+    let oracle = Keypair::new();
+    let authority = Keypair::new();
+    let mut context = start(&oracle.pubkey()).await;
+    send(
+        &mut context,
+        &[initialize_instruction(
+            &oracle.pubkey(),
+            &authority.pubkey(),
+            initialize_with_flags(OracleFlags::empty()),
+        )],
+        &[&oracle],
+    )
+    .await
+    .unwrap();
+    let before = oracle_state(&mut context, &oracle.pubkey()).await;
+
+    let samples: Vec<(i64, u64, u64)> = (1..=5).map(|i| (i * 1_000, i as u64, i as u64 * 10)).collect();
+    let batch = OracleInstruction::UpdateBatchSameAccount(UpdateBatchSameAccount {
+        samples: samples.clone(),
+    });
+    send(
+        &mut context,
+        &[authority_update_instruction(&oracle.pubkey(), &authority.pubkey(), batch)],
+        &[&authority],
+    )
+    .await
+    .unwrap();
+
+    let state = oracle_state(&mut context, &oracle.pubkey()).await;
+    let mut newest_first = samples;
+    newest_first.reverse();
+    assert_eq!(state.recent_prices(), newest_first);
+    assert_eq!(state.history_cursor(), 5);
+    // the aggregate is untouched
+    assert_eq!(state.price_component, before.price_component);
+    assert_eq!(state.publish_slot, before.publish_slot);
+    assert_eq!(state.sequence(), before.sequence());
+}
+
+#[tokio::test]
+async fn test_add_publisher_rejects_inactive_component() {
+    let oracle = Keypair::new();
+    let authority = Keypair::new();
+    let mut context = start(&oracle.pubkey()).await;
+    send(
+        &mut context,
+        &[initialize_instruction(
+            &oracle.pubkey(),
+            &authority.pubkey(),
+            initialize_with_flags(OracleFlags::empty()),
+        )],
+        &[&oracle],
+    )
+    .await
+    .unwrap();
+    let state = oracle_state(&mut context, &oracle.pubkey()).await;
+
+    let add = OracleInstruction::AddPublisher(AddPublisher {
+        index: state.active_component_count() as u8,
+        publisher: Pubkey::new_unique(),
+    });
+    let error = send(
+        &mut context,
+        &[authority_instruction(&oracle.pubkey(), &authority.pubkey(), add)],
+        &[&authority],
+    )
+    .await
+    .unwrap_err();
+    assert_eq!(
+        error,
+        TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(OracleError::ComponentIndexOutOfRange as u32)
+        )
+    );
+}
+
+#[tokio::test]
+async fn test_two_step_authority_handoff() {
+    let oracle = Keypair::new();
+    let authority = Keypair::new();
+    let new_authority = Keypair::new();
+    let stranger = Keypair::new();
+    let mut context = start(&oracle.pubkey()).await;
+    send(
+        &mut context,
+        &[initialize_instruction(
+            &oracle.pubkey(),
+            &authority.pubkey(),
+            initialize_with_flags(OracleFlags::empty()),
+        )],
+        &[&oracle],
+    )
+    .await
+    .unwrap();
+    let incorrect_authority = TransactionError::InstructionError(
+        0,
+        InstructionError::Custom(OracleError::IncorrectAuthority as u32),
+    );
+
+    // nothing to accept before a proposal
+    let error = send(
+        &mut context,
+        &[authority_instruction(
+            &oracle.pubkey(),
+            &stranger.pubkey(),
+            OracleInstruction::AcceptAuthority,
+        )],
+        &[&stranger],
+    )
+    .await
+    .unwrap_err();
+    assert_eq!(error, incorrect_authority);
+
+    let propose = OracleInstruction::ProposeAuthority {
+        new_authority: new_authority.pubkey(),
+    };
+    send(
+        &mut context,
+        &[authority_instruction(&oracle.pubkey(), &authority.pubkey(), propose)],
+        &[&authority],
+    )
+    .await
+    .unwrap();
+    let state = oracle_state(&mut context, &oracle.pubkey()).await;
+    assert_eq!(state.authority(), authority.pubkey());
+    assert_eq!(state.pending_authority(), new_authority.pubkey());
+
+    // only the proposed authority can accept, not even the current one
+    let error = send(
+        &mut context,
+        &[authority_instruction(
+            &oracle.pubkey(),
+            &authority.pubkey(),
+            OracleInstruction::AcceptAuthority,
+        )],
+        &[&authority],
+    )
+    .await
+    .unwrap_err();
+    assert_eq!(error, incorrect_authority);
+    let state = oracle_state(&mut context, &oracle.pubkey()).await;
+    assert_eq!(state.authority(), authority.pubkey());
+    assert_eq!(state.pending_authority(), new_authority.pubkey());
+
+    send(
+        &mut context,
+        &[authority_instruction(
+            &oracle.pubkey(),
+            &new_authority.pubkey(),
+            OracleInstruction::AcceptAuthority,
+        )],
+        &[&new_authority],
+    )
+    .await
+    .unwrap();
+    let state = oracle_state(&mut context, &oracle.pubkey()).await;
+    assert_eq!(state.authority(), new_authority.pubkey());
+    assert_eq!(state.pending_authority(), Pubkey::default());
+
+    // the old authority is locked out, the new one takes over
+    let set_metadata = |metadata| OracleInstruction::SetMetadata { metadata };
+    let error = send(
+        &mut context,
+        &[authority_instruction(&oracle.pubkey(), &authority.pubkey(), set_metadata([1; 32]))],
+        &[&authority],
+    )
+    .await
+    .unwrap_err();
+    assert_eq!(error, incorrect_authority);
+    send(
+        &mut context,
+        &[authority_instruction(
+            &oracle.pubkey(),
+            &new_authority.pubkey(),
+            set_metadata([2; 32]),
+        )],
+        &[&new_authority],
+    )
+    .await
+    .unwrap();
+    assert_eq!(oracle_state(&mut context, &oracle.pubkey()).await.metadata(), [2; 32]);
+}
+
+#[tokio::test]
+async fn test_set_metadata_keeps_price() {
+    let oracle = Keypair::new();
+    let authority = Keypair::new();
+    let mut context = start(&oracle.pubkey()).await;
+    let mut metadata = [0; 32];
+    for (index, byte) in metadata.iter_mut().enumerate() {
+        *byte = index as u8 + 1;
+    }
+    let set_metadata = OracleInstruction::SetMetadata { metadata };
+    let data = set_metadata.pack();
+    assert_eq!(OracleInstruction::unpack(&data), Ok(OracleInstruction::SetMetadata { metadata }));
+
+    send(
+        &mut context,
+        &[
+            initialize_instruction(
+                &oracle.pubkey(),
+                &authority.pubkey(),
+                initialize_with_flags(OracleFlags::empty()),
+            ),
+            update_instruction(
+                &oracle.pubkey(),
+                true,
+                Update {
+                    price: 1_000_000,
+                    confidence: 100,
+                    status: PriceStatus::Trading.into(),
+                },
+            ),
+        ],
+        &[&oracle],
+    )
+    .await
+    .unwrap();
+    let before = oracle_state(&mut context, &oracle.pubkey()).await;
+
+    send(
+        &mut context,
+        &[authority_instruction(&oracle.pubkey(), &authority.pubkey(), set_metadata)],
+        &[&authority],
+    )
+    .await
+    .unwrap();
+    let state = oracle_state(&mut context, &oracle.pubkey()).await;
+    assert_eq!(state.metadata(), metadata);
+    assert_eq!(state.price_component, before.price_component);
+    assert_eq!(state.confidence_component, before.confidence_component);
+    assert_eq!(state.publish_slot, before.publish_slot);
+}
+
+#[tokio::test]
+async fn test_set_price_type_accepts_known_types_only() {
+    let oracle = Keypair::new();
+    let authority = Keypair::new();
+    let mut context = start(&oracle.pubkey()).await;
+    send(
+        &mut context,
+        &[initialize_instruction(
+            &oracle.pubkey(),
+            &authority.pubkey(),
+            initialize_with_flags(OracleFlags::empty()),
+        )],
+        &[&oracle],
+    )
+    .await
+    .unwrap();
+    let state = oracle_state(&mut context, &oracle.pubkey()).await;
+    assert_eq!(state.price_type_enum(), Ok(PriceType::Price));
+
+    for &price_type in [PriceType::Rate, PriceType::Volatility, PriceType::Price].iter() {
+        send(
+            &mut context,
+            &[authority_instruction(
+                &oracle.pubkey(),
+                &authority.pubkey(),
+                OracleInstruction::SetPriceType {
+                    price_type: price_type.into(),
+                },
+            )],
+            &[&authority],
+        )
+        .await
+        .unwrap();
+        let state = oracle_state(&mut context, &oracle.pubkey()).await;
+        assert_eq!(state.price_type_enum(), Ok(price_type));
+    }
+
+    for &price_type in [0, 4, u32::MAX].iter() {
+        let error = send(
+            &mut context,
+            &[authority_instruction(
+                &oracle.pubkey(),
+                &authority.pubkey(),
+                OracleInstruction::SetPriceType { price_type },
+            )],
+            &[&authority],
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(
+            error,
+            TransactionError::InstructionError(
+                0,
+                InstructionError::Custom(OracleError::InvalidPriceType as u32)
+            )
+        );
+    }
+    let state = oracle_state(&mut context, &oracle.pubkey()).await;
+    assert_eq!(state.price_type_enum(), Ok(PriceType::Price));
+}
+
+#[tokio::test]
+async fn test_skip_buffer_update_leaves_buffer_untouched() {
+    let oracle = Keypair::new();
+    let authority = Keypair::new();
+    let mut context = start(&oracle.pubkey()).await;
+    // history backfilled before the first update gives the buffer content
+    let batch = OracleInstruction::UpdateBatchSameAccount(UpdateBatchSameAccount {
+        samples: vec![(1_000, 1, 10), (2_000, 2, 20)],
+    });
+    send(
+        &mut context,
+        &[
+            initialize_instruction(
+                &oracle.pubkey(),
+                &authority.pubkey(),
+                initialize_with_flags(OracleFlags::SKIP_BUFFER),
+            ),
+            authority_update_instruction(&oracle.pubkey(), &authority.pubkey(), batch),
+        ],
+        &[&oracle, &authority],
+    )
+    .await
+    .unwrap();
+    let counters = [SEQUENCE_SLOT, UPDATE_COUNT_SLOT, QUOTE_SLOT];
+
+    let mut before = account_data(&mut context, &oracle.pubkey()).await;
+    for (i, instruction) in vec![
+        OracleInstruction::Update(Update {
+            price: 1_000_000,
+            confidence: 100,
+            status: PriceStatus::Trading.into(),
+        }),
+        OracleInstruction::UpdateQuote(UpdateQuote {
+            bid: 999_000,
+            ask: 1_001_000,
+            status: PriceStatus::Trading.into(),
+        }),
+    ]
+    .into_iter()
+    .enumerate()
+    {
+        send(
+            &mut context,
+            &[signed_oracle_instruction(&oracle.pubkey(), instruction)],
+            &[&oracle],
+        )
+        .await
+        .unwrap();
+        let after = account_data(&mut context, &oracle.pubkey()).await;
+        assert_ne!(before[..HEADER_LEN], after[..HEADER_LEN]);
+        for slot in 0..192 {
+            let range = HEADER_LEN + 16 * slot..HEADER_LEN + 16 * (slot + 1);
+            if !counters.contains(&slot) {
+                assert_eq!(before[range.clone()], after[range], "slot {}", slot);
+            }
+        }
+        let state = OracleV1::unpack_unchecked(&after).unwrap();
+        assert_eq!(state.sequence(), i as u64 + 1);
+        assert_eq!(state.update_count(), i as u64 + 1);
+        assert_eq!(state.history_cursor(), 2);
+        before = after;
+    }
+    let state = OracleV1::unpack_unchecked(&before).unwrap();
+    assert_eq!((state.bid(), state.ask()), (999_000, 1_001_000));
+}
+
+#[cfg(feature = "test-helpers")]
+#[tokio::test]
+async fn test_set_valid_slot_produces_stale_feed() {
+    use custom_oracle::instruction::SetValidSlot;
+
+    const MAX_STALENESS: u64 = 50;
+    let oracle = Keypair::new();
+    let authority = Keypair::new();
+    let mut context = start(&oracle.pubkey()).await;
+    send(
+        &mut context,
+        &[
+            initialize_instruction(
+                &oracle.pubkey(),
+                &authority.pubkey(),
+                Initialize {
+                    max_staleness_slots: MAX_STALENESS,
+                    ..initialize_with_flags(OracleFlags::empty())
+                },
+            ),
+            update_instruction(
+                &oracle.pubkey(),
+                true,
+                Update {
+                    price: 1_000_000,
+                    confidence: 100,
+                    status: PriceStatus::Trading.into(),
+                },
+            ),
+        ],
+        &[&oracle],
+    )
+    .await
+    .unwrap();
+    let state = oracle_state(&mut context, &oracle.pubkey()).await;
+    assert!(state.validity_duration(SLOT) <= state.max_staleness());
+
+    let set_valid_slot = |slot| {
+        OracleInstruction::SetValidSlot(SetValidSlot {
+            valid_slot: slot,
+            last_slot: slot,
+        })
+    };
+    // only the authority can rewind the feed
+    let stranger = Keypair::new();
+    let error = send(
+        &mut context,
+        &[authority_instruction(&oracle.pubkey(), &stranger.pubkey(), set_valid_slot(10))],
+        &[&stranger],
+    )
+    .await
+    .unwrap_err();
+    assert_eq!(
+        error,
+        TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(OracleError::IncorrectAuthority as u32)
+        )
+    );
+
+    send(
+        &mut context,
+        &[authority_instruction(&oracle.pubkey(), &authority.pubkey(), set_valid_slot(10))],
+        &[&authority],
+    )
+    .await
+    .unwrap();
+    let state = oracle_state(&mut context, &oracle.pubkey()).await;
+    assert_eq!((state.valid_slot, state.last_slot), (10, 10));
+    // a consumer bounding how long a price stays valid rejects the feed
+    assert!(state.validity_duration(SLOT) > state.max_staleness());
+}
@@ -0,0 +1,47 @@
+//! Packing then unpacking any `OracleV1` yields the same value
+
+use custom_oracle::state::OracleV1;
+use proptest::prelude::*;
+use solana_program::program_pack::Pack;
+
+fn price() -> impl Strategy<Value = i64> {
+    prop_oneof![
+        Just(i64::MIN),
+        Just(i64::MAX),
+        i64::MIN..0,
+        any::<i64>(),
+    ]
+}
+
+fn exponent() -> impl Strategy<Value = i32> {
+    prop_oneof![
+        Just(i32::MIN),
+        Just(i32::MAX),
+        -30..=30,
+        any::<i32>(),
+    ]
+}
+
+proptest! {
+    #[test]
+    fn test_pack_unpack_round_trip(
+        bytes in prop::collection::vec(any::<u8>(), OracleV1::LEN),
+        price in price(),
+        previous_price in price(),
+        exponent in exponent(),
+    ) {
+        let mut oracle = OracleV1::unpack_unchecked(&bytes).unwrap();
+        oracle.price_component = price;
+        oracle.previous_price_component = previous_price;
+        oracle.exponent = exponent;
+
+        let mut packed = vec![0; OracleV1::LEN];
+        oracle.pack_into_slice(&mut packed);
+        let unpacked = OracleV1::unpack_unchecked(&packed).unwrap();
+        prop_assert_eq!(&unpacked, &oracle);
+
+        let mut repacked = vec![0; OracleV1::LEN];
+        unpacked.pack_into_slice(&mut repacked);
+        prop_assert_eq!(repacked, packed);
+    }
+}
@@ -0,0 +1,52 @@
+//! Extreme values fed to the read-side helpers must never panic
+
+mod common;
+
+use common::zeroed;
+use custom_oracle::state::{OracleState, OracleV1};
+
+const PRICES: [i64; 5] = [i64::MIN, i64::MIN + 1, -1, 1, i64::MAX];
+const CONFIDENCES: [u64; 3] = [0, i64::MAX as u64, u64::MAX];
+
+fn oracle(price: i64, previous_price: i64, confidence: u64) -> OracleV1 {
+    let mut oracle = zeroed();
+    oracle.price_component = price;
+    oracle.previous_price_component = previous_price;
+    oracle.confidence_component = confidence;
+    oracle.ema_price_value = previous_price as u64;
+    oracle
+}
+
+#[test]
+fn test_helpers_do_not_panic_on_extremes() {
+    for &price in PRICES.iter() {
+        for &previous_price in PRICES.iter() {
+            for &confidence in CONFIDENCES.iter() {
+                let oracle = oracle(price, previous_price, confidence);
+                oracle.price_band();
+                oracle.price_change_bps();
+                oracle.within_tolerance(previous_price, u64::MAX);
+                oracle.ema_spot_divergence_bps();
+                oracle.confidence_pct();
+                oracle.price_q64();
+                oracle.scaled_price_for_amount(u64::MAX);
+                oracle.inverse_price_scaled(i32::MIN);
+                oracle.inverse_price_scaled(i32::MAX);
+                oracle.format_price();
+            }
+        }
+    }
+}
+
+#[test]
+fn test_price_band_saturates() {
+    assert_eq!(oracle(i64::MAX, 0, u64::MAX).price_band(), (0, i64::MAX));
+    assert_eq!(oracle(i64::MIN, 0, u64::MAX).price_band(), (i64::MIN, -1));
+}
+
+#[test]
+fn test_price_change_bps_saturates() {
+    assert_eq!(oracle(i64::MAX, 1, 0).price_change_bps(), Some(i64::MAX));
+    assert_eq!(oracle(i64::MIN, 1, 0).price_change_bps(), Some(i64::MIN));
+    assert_eq!(oracle(1, 0, 0).price_change_bps(), None);
+}
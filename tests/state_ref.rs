@@ -0,0 +1,191 @@
+//! Reads straight from packed bytes agree with a full unpack
+
+mod common;
+
+use common::zeroed;
+use custom_oracle::{
+    consts::VERSION,
+    state::{OracleState, OracleStateRef, OracleV1, OracleVersion},
+};
+use solana_program::{program_error::ProgramError, program_pack::Pack, pubkey::Pubkey};
+
+/// An oracle with a distinct value in every field, so a getter reading the
+/// wrong offset can't go unnoticed.
+fn populated() -> OracleV1 {
+    let mut oracle = zeroed();
+    oracle.magic = 0xa1b2c3d4;
+    oracle.version = 2;
+    oracle.acctype = 3;
+    oracle.size = OracleV1::LEN as u32;
+    oracle.price_type = 1;
+    oracle.exponent = -8;
+    oracle.num_component_prices = 10;
+    oracle.num_quoters = 4;
+    oracle.last_slot = 1_001;
+    oracle.valid_slot = 1_002;
+    oracle.ema_price_value = 1_003;
+    oracle.ema_price_numerator = 1_004;
+    oracle.ema_price_denominator = 1_005;
+    oracle.ema_confidence_value = 1_006;
+    oracle.ema_confidence_numerator = 1_007;
+    oracle.ema_confidence_denominator = 1_008;
+    oracle.timestamp = -1_009;
+    oracle.min_publishers = 7;
+    oracle.drv2 = -6;
+    oracle.drv3 = -9;
+    oracle.drv4 = 0x11;
+    oracle.product_account_key = Pubkey::new_from_array([1; 32]);
+    oracle.next_price_account_key = Pubkey::new_from_array([2; 32]);
+    oracle.previous_slot = 1_010;
+    oracle.previous_price_component = -1_011;
+    oracle.previous_confidence_component = 1_012;
+    oracle.previous_timestamp = 1_013;
+    oracle.price_component = -1_014;
+    oracle.confidence_component = 1_015;
+    oracle.status = 2;
+    oracle.corporate_action = 3;
+    oracle.publish_slot = 1_016;
+    for (index, slot) in oracle.buffer.iter_mut().enumerate() {
+        *slot = u128::MAX / (index as u128 + 2);
+    }
+    oracle
+}
+
+#[test]
+fn test_ref_getters_match_owned_unpack() {
+    let oracle = populated();
+    let data = oracle.to_bytes();
+    let view = OracleStateRef::new(&data).unwrap();
+
+    assert_eq!(view.magic(), oracle.magic());
+    assert_eq!(view.version(), oracle.version());
+    assert_eq!(view.acctype(), oracle.acctype());
+    assert_eq!(view.size(), oracle.size());
+    assert_eq!(view.price_type(), oracle.price_type());
+    assert_eq!(view.exponent(), oracle.exponent());
+    assert_eq!(view.num_component_prices(), oracle.num_component_prices());
+    assert_eq!(view.num_quoters(), oracle.num_quoters());
+    assert_eq!(view.last_slot(), oracle.last_slot());
+    assert_eq!(view.valid_slot(), oracle.valid_slot());
+    assert_eq!(view.ema_price_value(), oracle.ema_price_value());
+    assert_eq!(view.ema_price_numerator(), oracle.ema_price_numerator());
+    assert_eq!(view.ema_price_denominator(), oracle.ema_price_denominator());
+    assert_eq!(view.ema_confidence_value(), oracle.ema_confidence_value());
+    assert_eq!(view.ema_confidence_numerator(), oracle.ema_confidence_numerator());
+    assert_eq!(view.ema_confidence_denominator(), oracle.ema_confidence_denominator());
+    assert_eq!(view.timestamp(), oracle.timestamp());
+    assert_eq!(view.min_publishers(), oracle.min_publishers());
+    assert_eq!(view.drv2(), oracle.drv2());
+    assert_eq!(view.drv3(), oracle.drv3());
+    assert_eq!(view.drv4(), oracle.drv4());
+    assert_eq!(view.product_account_key(), oracle.product_account_key());
+    assert_eq!(view.next_price_account_key(), oracle.next_price_account_key());
+    assert_eq!(view.previous_slot(), oracle.previous_slot());
+    assert_eq!(view.previous_price_component(), oracle.previous_price_component());
+    assert_eq!(view.previous_confidence_component(), oracle.previous_confidence_component());
+    assert_eq!(view.previous_timestamp(), oracle.previous_timestamp());
+    assert_eq!(view.price_component(), oracle.price_component());
+    assert_eq!(view.confidence_component(), oracle.confidence_component());
+    assert_eq!(view.status(), oracle.status());
+    assert_eq!(view.corporate_action(), oracle.corporate_action());
+    assert_eq!(view.publish_slot(), oracle.publish_slot());
+    assert_eq!(view.buffer(), oracle.buffer());
+    for index in 0..192 {
+        assert_eq!(view.buffer_slot(index), oracle.buffer_slot(index));
+    }
+}
+
+#[test]
+fn test_ref_rejects_wrong_length() {
+    let data = populated().to_bytes();
+    assert!(OracleStateRef::new(&data[..OracleV1::LEN - 1]).is_err());
+    assert!(OracleStateRef::new(&[data.as_ref(), &[0]].concat()).is_err());
+}
+
+#[test]
+fn test_peek_version() {
+    let data = populated().to_bytes();
+    assert_eq!(OracleVersion::peek_version(&data), Ok(VERSION));
+    assert_eq!(OracleVersion::peek_version(&data[..8]), Ok(VERSION));
+    assert_eq!(
+        OracleVersion::peek_version(&data[..7]),
+        Err(ProgramError::InvalidAccountData)
+    );
+    assert_eq!(OracleVersion::peek_version(&[]), Err(ProgramError::InvalidAccountData));
+}
+
+#[test]
+fn test_unpack_v1_matches_boxed_unpack() {
+    let data = populated().to_bytes();
+    let oracle = OracleVersion::unpack_v1(&data).unwrap();
+    let boxed = OracleVersion::unpack(&data).unwrap();
+    assert_eq!(oracle, populated());
+
+    assert_eq!(boxed.magic(), oracle.magic());
+    assert_eq!(boxed.version(), oracle.version());
+    assert_eq!(boxed.acctype(), oracle.acctype());
+    assert_eq!(boxed.size(), oracle.size());
+    assert_eq!(boxed.price_type(), oracle.price_type());
+    assert_eq!(boxed.exponent(), oracle.exponent());
+    assert_eq!(boxed.num_component_prices(), oracle.num_component_prices());
+    assert_eq!(boxed.num_quoters(), oracle.num_quoters());
+    assert_eq!(boxed.last_slot(), oracle.last_slot());
+    assert_eq!(boxed.valid_slot(), oracle.valid_slot());
+    assert_eq!(boxed.ema_price_value(), oracle.ema_price_value());
+    assert_eq!(boxed.ema_price_numerator(), oracle.ema_price_numerator());
+    assert_eq!(boxed.ema_price_denominator(), oracle.ema_price_denominator());
+    assert_eq!(boxed.ema_confidence_value(), oracle.ema_confidence_value());
+    assert_eq!(boxed.ema_confidence_numerator(), oracle.ema_confidence_numerator());
+    assert_eq!(boxed.ema_confidence_denominator(), oracle.ema_confidence_denominator());
+    assert_eq!(boxed.timestamp(), oracle.timestamp());
+    assert_eq!(boxed.min_publishers(), oracle.min_publishers());
+    assert_eq!(boxed.drv2(), oracle.drv2());
+    assert_eq!(boxed.drv3(), oracle.drv3());
+    assert_eq!(boxed.drv4(), oracle.drv4());
+    assert_eq!(boxed.product_account_key(), oracle.product_account_key());
+    assert_eq!(boxed.next_price_account_key(), oracle.next_price_account_key());
+    assert_eq!(boxed.previous_slot(), oracle.previous_slot());
+    assert_eq!(boxed.previous_price_component(), oracle.previous_price_component());
+    assert_eq!(boxed.previous_confidence_component(), oracle.previous_confidence_component());
+    assert_eq!(boxed.previous_timestamp(), oracle.previous_timestamp());
+    assert_eq!(boxed.price_component(), oracle.price_component());
+    assert_eq!(boxed.confidence_component(), oracle.confidence_component());
+    assert_eq!(boxed.status(), oracle.status());
+    assert_eq!(boxed.corporate_action(), oracle.corporate_action());
+    assert_eq!(boxed.publish_slot(), oracle.publish_slot());
+    assert_eq!(boxed.buffer(), oracle.buffer());
+}
+
+#[test]
+fn test_unpack_into_matches_unpack_v1() {
+    let data = populated().to_bytes();
+    let mut out = zeroed();
+    OracleVersion::unpack_into(&data, &mut out).unwrap();
+    assert_eq!(out, OracleVersion::unpack_v1(&data).unwrap());
+
+    // an uninitialized account fails the same way as the copying unpack
+    let mut bad = populated();
+    bad.magic = 0;
+    let data = bad.to_bytes();
+    assert_eq!(
+        OracleVersion::unpack_into(&data, &mut out).unwrap_err(),
+        OracleVersion::unpack_v1(&data).unwrap_err()
+    );
+}
+
+#[test]
+fn test_read_status_and_price_match_unpack() {
+    let oracle = populated();
+    let data = oracle.to_bytes();
+    assert_eq!(OracleVersion::read_status(&data), Ok(oracle.status));
+    assert_eq!(OracleVersion::read_price_raw(&data), Ok(oracle.price_component));
+
+    assert_eq!(
+        OracleVersion::read_status(&data[..227]),
+        Err(ProgramError::InvalidAccountData)
+    );
+    assert_eq!(
+        OracleVersion::read_price_raw(&data[..215]),
+        Err(ProgramError::InvalidAccountData)
+    );
+}
@@ -0,0 +1,129 @@
+//! `validate_instruction` accepts what `unpack` accepts and rejects unknown
+//! tags and short payloads
+
+use custom_oracle::{
+    error::OracleError,
+    instruction::{
+        validate_instruction, AddPublisher, Initialize, OracleInstruction, Update,
+        UpdateBatchSameAccount, UpdateConfidence, UpdateQuote, UpdateWide, MAX_BATCH_SAMPLES,
+    },
+    state::OracleFlags,
+};
+use solana_program::pubkey::Pubkey;
+
+fn instructions() -> Vec<OracleInstruction> {
+    let update = Update {
+        price: 1_000_000,
+        confidence: 100,
+        status: 1,
+    };
+    vec![
+        OracleInstruction::Update(update),
+        OracleInstruction::Initialize(Initialize {
+            min_confidence: 0,
+            reject_below_min_confidence: false,
+            flags: OracleFlags::empty(),
+            base_decimals: 0,
+            quote_decimals: 0,
+            aggregation_trim: 0,
+            initial_price: 0,
+            max_staleness_slots: 0,
+            valid_slot_delay: 0,
+            num_quoters: 1,
+        }),
+        OracleInstruction::UpdateBatchSameAccount(UpdateBatchSameAccount {
+            samples: vec![(1, 2, 3), (4, 5, 6)],
+        }),
+        OracleInstruction::AddPublisher(AddPublisher {
+            index: 0,
+            publisher: Pubkey::new_unique(),
+        }),
+        OracleInstruction::RemovePublisher { index: 0 },
+        OracleInstruction::UpdateWide(UpdateWide {
+            price: i128::MAX,
+            confidence: 100,
+            status: 1,
+        }),
+        OracleInstruction::SetMetadata { metadata: [7; 32] },
+        OracleInstruction::SetPriceType { price_type: 2 },
+        OracleInstruction::UpdateQuote(UpdateQuote {
+            bid: 99,
+            ask: 101,
+            status: 1,
+        }),
+        OracleInstruction::UpdateConfidence(UpdateConfidence {
+            confidence: 100,
+            status: 1,
+        }),
+    ]
+}
+
+#[test]
+fn test_packed_instructions_validate() {
+    for instruction in instructions() {
+        let mut data = instruction.pack();
+        assert_eq!(validate_instruction(&data), Ok(()), "{:?}", instruction);
+        OracleInstruction::unpack(&data).unwrap();
+
+        // trailing bytes are ignored
+        data.push(0);
+        assert_eq!(validate_instruction(&data), Ok(()), "{:?}", instruction);
+        OracleInstruction::unpack(&data).unwrap();
+    }
+}
+
+#[test]
+fn test_short_instructions_are_rejected() {
+    for instruction in instructions() {
+        let data = instruction.pack();
+        assert_eq!(
+            validate_instruction(&data[..data.len() - 1]),
+            Err(OracleError::InvalidInstruction),
+            "{:?}",
+            instruction
+        );
+        assert!(OracleInstruction::unpack(&data[..data.len() - 1]).is_err());
+    }
+
+    // a batch shorter than its sample count
+    let mut data = OracleInstruction::UpdateBatchSameAccount(UpdateBatchSameAccount {
+        samples: vec![(1, 2, 3)],
+    })
+    .pack();
+    data[1] = 2;
+    assert_eq!(validate_instruction(&data), Err(OracleError::InvalidInstruction));
+    assert_eq!(validate_instruction(&[4]), Err(OracleError::InvalidInstruction));
+}
+
+#[test]
+fn test_unknown_tags_are_rejected() {
+    assert_eq!(validate_instruction(&[]), Err(OracleError::InvalidInstruction));
+    for tag in 22..=u8::MAX {
+        let mut data = vec![tag];
+        data.extend_from_slice(&[0; 64]);
+        assert_eq!(validate_instruction(&data), Err(OracleError::InvalidInstruction), "{}", tag);
+        assert!(OracleInstruction::unpack(&data).is_err());
+    }
+}
+
+#[cfg(not(feature = "test-helpers"))]
+#[test]
+fn test_test_helper_tags_are_rejected() {
+    for &tag in [1, 20].iter() {
+        let mut data = vec![tag];
+        data.extend_from_slice(&[0; 64]);
+        assert_eq!(validate_instruction(&data), Err(OracleError::InvalidInstruction), "{}", tag);
+    }
+}
+
+#[test]
+fn test_batch_of_more_than_255_samples_is_rejected() {
+    let batch = UpdateBatchSameAccount::new(vec![(1, 2, 3); MAX_BATCH_SAMPLES]).unwrap();
+    let data = OracleInstruction::UpdateBatchSameAccount(batch).pack();
+    assert_eq!(data[1], 255);
+    assert_eq!(validate_instruction(&data), Ok(()));
+    assert_eq!(
+        UpdateBatchSameAccount::new(vec![(1, 2, 3); MAX_BATCH_SAMPLES + 1]),
+        Err(OracleError::InvalidInstruction)
+    );
+}